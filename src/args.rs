@@ -0,0 +1,35 @@
+use anyhow::Result;
+use clap::{crate_authors, crate_description, crate_name, crate_version, App as ClapApp, Arg};
+
+pub struct CliArgs {
+    pub host: Option<String>,
+    pub all: bool,
+}
+
+pub fn process_cmdline() -> Result<CliArgs> {
+    let app = ClapApp::new(crate_name!())
+        .author(crate_authors!())
+        .version(crate_version!())
+        .about(crate_description!())
+        .arg(
+            Arg::with_name("host")
+                .help("Docker daemon to connect to, e.g. unix:///var/run/docker.sock or tcp://127.0.0.1:2375")
+                .short("H")
+                .long("host")
+                .value_name("URL")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("all")
+                .help("Show stopped containers as well as running ones")
+                .short("a")
+                .long("all"),
+        );
+
+    let arg_matches = app.get_matches();
+
+    Ok(CliArgs {
+        host: arg_matches.value_of("host").map(String::from),
+        all: arg_matches.is_present("all"),
+    })
+}
@@ -0,0 +1,56 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Result};
+
+fn exec_copy(command: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut process = Command::new(command)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| anyhow!("failed to run {}: {}", command, err))?;
+
+    process
+        .stdin
+        .as_mut()
+        .ok_or_else(|| anyhow!("failed to get stdin of {}", command))?
+        .write_all(text.as_bytes())?;
+
+    let status = process.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", command, status));
+    }
+    Ok(())
+}
+
+/// Uses whichever clipboard tool of the running session is installed
+#[cfg(target_os = "linux")]
+pub fn copy_string(text: &str) -> Result<()> {
+    let tools: [(&str, &[&str]); 3] = [
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+    let (command, args) = tools
+        .iter()
+        .find(|(command, _)| which::which(command).is_ok())
+        .ok_or_else(|| anyhow!("no clipboard tool found, install wl-copy, xclip or xsel"))?;
+    exec_copy(command, args, text)
+}
+
+#[cfg(target_os = "macos")]
+pub fn copy_string(text: &str) -> Result<()> {
+    exec_copy("pbcopy", &[], text)
+}
+
+#[cfg(windows)]
+pub fn copy_string(text: &str) -> Result<()> {
+    exec_copy("clip", &[], text)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn copy_string(_text: &str) -> Result<()> {
+    Err(anyhow!("copying to the clipboard is not supported on this platform"))
+}
@@ -1,56 +1,719 @@
-use crate::components::{DrawableComponent, MutableDrawableComponent};
-use tui::layout::{Rect, Alignment};
+use crate::components::MutableDrawableComponent;
+use tui::layout::Rect;
 use tui::Frame;
 use anyhow::Error;
 use tui::backend::Backend;
-use tui::widgets::{Paragraph, Block, Borders, ListItem, List};
-use crate::components::util::StatefulList;
+use tui::buffer::Buffer;
+use tui::style::Style;
+use tui::widgets::{Block, Borders, Widget};
+use crate::components::util::{format_age, hidden_marks_note, mark_symbol, short_id, Keyed, SortKey, SortOrder, StatefulList};
+use crate::components::util::filter::{Filter, Searchable};
 use bollard::service::ContainerSummaryInner;
-use tui::style::{Style, Modifier, Color};
 use tui::text::{Span, Spans};
-use crate::components::main_app::MainApp;
+use crate::components::main_app::{MainApp, Pane};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+pub const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+pub const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+
+const HIGHLIGHT_SYMBOL: &str = ">> ";
+
+/// A column of the containers table
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ContainerColumn {
+    Name,
+    Id,
+    Image,
+    Command,
+    Status,
+    Health,
+    Ports,
+    Created,
+    Project,
+}
+
+impl ContainerColumn {
+    fn header(self) -> &'static str {
+        match self {
+            ContainerColumn::Name => "Name",
+            ContainerColumn::Id => "ID",
+            ContainerColumn::Image => "Image",
+            ContainerColumn::Command => "Command",
+            ContainerColumn::Status => "Status",
+            ContainerColumn::Health => "Health",
+            ContainerColumn::Ports => "Ports",
+            ContainerColumn::Created => "Created",
+            ContainerColumn::Project => "Project",
+        }
+    }
+
+    fn value(self, container: &ContainerSummaryInner) -> String {
+        match self {
+            ContainerColumn::Name => display_name(container),
+            ContainerColumn::Id => short_id(container.id.as_deref().unwrap_or_default()).to_string(),
+            ContainerColumn::Image => container.image.clone().unwrap_or_default(),
+            ContainerColumn::Command => container.command.clone().unwrap_or_default(),
+            ContainerColumn::Status => container.status.clone().unwrap_or_default(),
+            ContainerColumn::Health => health(container).unwrap_or_default().to_string(),
+            ContainerColumn::Ports => published_ports(container),
+            ContainerColumn::Created => container.created.map(format_age).unwrap_or_default(),
+            ContainerColumn::Project => compose_project(container).unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// What the containers are sorted by
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ContainerSort {
+    Name,
+    Created,
+    Status,
+    Cpu,
+}
+
+impl SortKey for ContainerSort {
+    const ALL: &'static [ContainerSort] = &[ContainerSort::Name, ContainerSort::Created, ContainerSort::Status, ContainerSort::Cpu];
+
+    fn name(self) -> &'static str {
+        match self {
+            ContainerSort::Name => "name",
+            ContainerSort::Created => "created",
+            ContainerSort::Status => "status",
+            ContainerSort::Cpu => "cpu",
+        }
+    }
+
+    fn descending(self) -> bool {
+        matches!(self, ContainerSort::Created | ContainerSort::Cpu)
+    }
+}
+
+/// A column and its width as a percentage of the table
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColumnConfig {
+    pub column: ContainerColumn,
+    pub width: u16,
+}
+
+impl ColumnConfig {
+    pub fn new(column: ContainerColumn, width: u16) -> ColumnConfig {
+        ColumnConfig { column, width }
+    }
+
+    pub fn defaults() -> Vec<ColumnConfig> {
+        vec![
+            ColumnConfig::new(ContainerColumn::Name, 15),
+            ColumnConfig::new(ContainerColumn::Id, 9),
+            ColumnConfig::new(ContainerColumn::Image, 15),
+            ColumnConfig::new(ContainerColumn::Command, 12),
+            ColumnConfig::new(ContainerColumn::Status, 14),
+            ColumnConfig::new(ContainerColumn::Health, 7),
+            ColumnConfig::new(ContainerColumn::Ports, 13),
+            ColumnConfig::new(ContainerColumn::Created, 8),
+            ColumnConfig::new(ContainerColumn::Project, 7),
+        ]
+    }
+}
+
+/// A compose project, or one of its services, and the containers in it
+pub struct ContainerGroup {
+    pub project: String,
+    pub service: Option<String>,
+    /// Identifies the group across refreshes, e.g. `shop` or `shop/web`
+    key: String,
+    /// Indices into the containers of the list
+    containers: Vec<usize>,
+}
+
+impl ContainerGroup {
+    fn new(project: &str, service: Option<&str>, containers: Vec<usize>) -> ContainerGroup {
+        let key = match service {
+            Some(service) => format!("{}/{}", project, service),
+            None => project.to_string(),
+        };
+        ContainerGroup {
+            project: project.to_string(),
+            service: service.map(str::to_string),
+            key,
+            containers,
+        }
+    }
+
+    /// Name used in notices, e.g. `project shop` or `service shop/web`
+    pub fn label(&self) -> String {
+        match self.service {
+            Some(_) => format!("service {}", self.key),
+            None => format!("project {}", self.project),
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.service.as_deref().unwrap_or(&self.project)
+    }
+}
+
+pub enum ContainerRow {
+    Group { group: ContainerGroup, depth: usize, collapsed: bool },
+    Container { index: usize, id: String, depth: usize },
+}
+
+impl Keyed for ContainerRow {
+    fn key(&self) -> &str {
+        match self {
+            ContainerRow::Group { group, .. } => &group.key,
+            ContainerRow::Container { id, .. } => id,
+        }
+    }
+}
 
 pub struct Containers {
-    selected: usize,
-    items: StatefulList<ContainerSummaryInner>
+    containers: Vec<ContainerSummaryInner>,
+    items: StatefulList<ContainerRow>,
+    /// Keys of the groups folded away
+    collapsed: HashSet<String>,
+    /// Ids of the containers marked for a bulk action, folded and hidden ones included
+    marked: HashSet<String>,
+    grouped: bool,
+    /// Whether the stopped containers are listed
+    all: bool,
+    filter: Filter,
+    sort: SortOrder<ContainerSort>,
+    /// Latest cpu usage of the running containers by id in whole percent, for sorting
+    /// by cpu without reordering the rows over every fraction of a percent
+    cpu: HashMap<String, u64>,
+    /// First row drawn
+    offset: usize,
+    height: usize,
 }
+
 impl MutableDrawableComponent for Containers {
     fn draw<B: Backend>(&mut self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
-        let items: Vec<ListItem> = app.containers
+        let focused = app.is_focused(Pane::Containers);
+        let title = if app.all_containers { "Containers (all)" } else { "Containers" };
+        let title = format!("{} {}", title, self.sort.label());
+        let title = format!("{}{}", self.filter.title(&title), hidden_marks_note(self.hidden_marks()));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(app.theme.block(focused))
+            .title(Span::styled(title, app.theme.title(focused)));
+        let area = block.inner(rect);
+        f.render_widget(block, rect);
+
+        // header and the gap below it
+        self.height = area.height.saturating_sub(2) as usize;
+        let selected = self.items.state.selected();
+        if let Some(selected) = selected {
+            if selected < self.offset {
+                self.offset = selected;
+            } else if self.height > 0 && selected >= self.offset + self.height {
+                self.offset = selected + 1 - self.height;
+            }
+        }
+        self.offset = self.offset.min(self.items.items.len().saturating_sub(self.height));
+
+        let columns = &app.options.container_columns;
+        let all = &self.containers;
+        let marked = &self.marked;
+        let filter = &self.filter;
+        let highlight = app.theme.filter_match();
+        let is_marked = |index: &usize| all[*index].id.as_ref().map_or(false, |id| marked.contains(id));
+        let rows: Vec<(Vec<Vec<Span>>, Style)> = self.items.items
             .iter()
-            .map(|i| {
-                let names = i.names.as_ref().unwrap();
-                let mut lines = vec![];
-
-                names.iter().for_each(|name| {
-                    lines.push(Spans::from(Span::styled(
-                        name,
-                        Style::default().add_modifier(Modifier::ITALIC),
-                    )));
-                });
-                ListItem::new(lines).style(Style::default().fg(Color::Black).bg(Color::White))
+            .skip(self.offset)
+            .take(self.height)
+            .map(|row| match row {
+                ContainerRow::Group { group, depth, collapsed } => {
+                    let containers: Vec<&ContainerSummaryInner> = group.containers.iter().map(|i| &all[*i]).collect();
+                    let running = containers.iter().filter(|container| container.state.as_deref() == Some("running")).count();
+                    // a group is marked when all of its containers are
+                    let mark = mark_symbol(group.containers.iter().all(is_marked), !marked.is_empty());
+                    let cells = columns
+                        .iter()
+                        .map(|config| match config.column {
+                            ContainerColumn::Name => {
+                                let arrow = if *collapsed { '▸' } else { '▾' };
+                                format!("{}{} {}", "  ".repeat(*depth), arrow, group.name())
+                            }
+                            ContainerColumn::Status => format!("{}/{} running", running, containers.len()),
+                            ContainerColumn::Project => group.project.clone(),
+                            _ => String::new(),
+                        })
+                        .map(|value| vec![Span::raw(value)])
+                        .collect();
+                    (with_mark(cells, mark), app.theme.container_group(running, containers.len()))
+                }
+                ContainerRow::Container { index, depth, .. } => {
+                    let container = &all[*index];
+                    let mark = mark_symbol(is_marked(index), !marked.is_empty());
+                    let cells = columns
+                        .iter()
+                        .map(|config| match config.column {
+                            ContainerColumn::Name => {
+                                let mut cell = vec![Span::raw("  ".repeat(*depth))];
+                                cell.extend(filter.spans(display_name(container), Style::default(), highlight));
+                                cell
+                            }
+                            column @ ContainerColumn::Id | column @ ContainerColumn::Image => {
+                                filter.spans(column.value(container), Style::default(), highlight)
+                            }
+                            column => vec![Span::raw(column.value(container))],
+                        })
+                        .collect();
+                    (with_mark(cells, mark), app.theme.container_state(container.state.as_deref().unwrap_or_default()))
+                }
             })
             .collect();
 
-        let items = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Containers"))
-            .highlight_style(
-                Style::default()
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">> ");
-        f.render_stateful_widget(items, rect, &mut self.items.state);
+        // widths are in percent of what is left besides the highlight symbol and the gaps between columns
+        let gaps = HIGHLIGHT_SYMBOL.len() + columns.len().saturating_sub(1);
+        let available = (area.width as u32).saturating_sub(gaps as u32);
+        let widths = columns.iter().map(|config| (available * config.width.min(100) as u32 / 100) as u16).collect();
 
+        f.render_widget(
+            CellTable {
+                header: columns.iter().map(|config| config.column.header()).collect(),
+                rows,
+                widths,
+                selected: selected.map(|selected| selected - self.offset),
+                header_style: app.theme.title(focused),
+                highlight_style: app.theme.text(true, true),
+            },
+            area,
+        );
         Ok(())
     }
 }
 
+/// A table whose cells are spans, tui's `Table` only takes plain text and
+/// can't show which chars of a cell the filter matched
+struct CellTable<'a> {
+    header: Vec<&'a str>,
+    /// Cells of the rows that fit and the style of each row
+    rows: Vec<(Vec<Vec<Span<'a>>>, Style)>,
+    widths: Vec<u16>,
+    selected: Option<usize>,
+    header_style: Style,
+    highlight_style: Style,
+}
+
+impl<'a> Widget for CellTable<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let start = area.x + HIGHLIGHT_SYMBOL.len() as u16;
+        let mut x = start;
+        for (header, width) in self.header.iter().zip(&self.widths) {
+            buf.set_stringn(x, area.y, header, *width as usize, self.header_style);
+            x = x.saturating_add(width + 1);
+        }
+
+        // a gap below the header like tui's table
+        for (i, (cells, style)) in self.rows.into_iter().enumerate() {
+            let y = area.y + 2 + i as u16;
+            if y >= area.bottom() {
+                break;
+            }
+            let style = if self.selected == Some(i) { style.patch(self.highlight_style) } else { style };
+            buf.set_style(Rect::new(area.x, y, area.width, 1), style);
+            if self.selected == Some(i) {
+                buf.set_stringn(area.x, y, HIGHLIGHT_SYMBOL, area.width as usize, style);
+            }
+            let mut x = start;
+            for (cell, width) in cells.into_iter().zip(&self.widths) {
+                let width = (*width).min(area.right().saturating_sub(x));
+                buf.set_spans(x, y, &Spans::from(cell), width);
+                x = x.saturating_add(width + 1);
+            }
+        }
+    }
+}
+
 impl Containers {
-    pub fn new() -> Containers {
+    pub fn new(sort: SortOrder<ContainerSort>) -> Containers {
         Containers {
-            selected: 0,
-            items: StatefulList::new()
+            containers: vec![],
+            items: StatefulList::new(),
+            collapsed: HashSet::new(),
+            marked: HashSet::new(),
+            grouped: true,
+            all: false,
+            filter: Filter::default(),
+            sort,
+            cpu: HashMap::new(),
+            offset: 0,
+            height: 0,
         }
     }
-}
\ No newline at end of file
+
+    /// Lists the containers, in a tree of compose projects and their services when
+    /// `grouped` and the stopped ones only when `all`. Marks are dropped only for
+    /// the containers that are gone.
+    pub fn set_items(&mut self, containers: Vec<ContainerSummaryInner>, grouped: bool, all: bool) {
+        let ids: HashSet<&str> = containers.iter().filter_map(|container| container.id.as_deref()).collect();
+        self.marked.retain(|id| ids.contains(id.as_str()));
+        self.containers = containers;
+        self.grouped = grouped;
+        self.all = all;
+        self.update_rows();
+    }
+
+    /// Projects and services are listed by name, the containers in them and those
+    /// outside of a project in the order of the sort
+    fn update_rows(&mut self) {
+        let mut order: Vec<usize> = (0..self.containers.len())
+            .filter(|index| self.is_listed(&self.containers[*index]))
+            .collect();
+        order.sort_by(|a, b| self.compare(&self.containers[*a], &self.containers[*b]));
+
+        let mut rows = vec![];
+        let mut ungrouped = vec![];
+        let mut projects: Vec<(&str, Vec<usize>)> = vec![];
+        for index in order {
+            let container = &self.containers[index];
+            match compose_project(container) {
+                Some(project) if self.grouped => match projects.iter_mut().find(|(name, _)| *name == project) {
+                    Some((_, containers)) => containers.push(index),
+                    None => projects.push((project, vec![index])),
+                },
+                _ => ungrouped.push(index),
+            }
+        }
+        projects.sort_by_key(|(name, _)| *name);
+
+        for (project, containers) in projects {
+            let mut services: Vec<(&str, Vec<usize>)> = vec![];
+            for &index in &containers {
+                let service = compose_service(&self.containers[index]).unwrap_or_default();
+                match services.iter_mut().find(|(name, _)| *name == service) {
+                    Some((_, containers)) => containers.push(index),
+                    None => services.push((service, vec![index])),
+                }
+            }
+            services.sort_by_key(|(name, _)| *name);
+
+            let group = ContainerGroup::new(project, None, containers);
+            let collapsed = self.collapsed.contains(&group.key);
+            rows.push(ContainerRow::Group { group, depth: 0, collapsed });
+            if collapsed {
+                continue;
+            }
+            for (service, containers) in services {
+                let group = ContainerGroup::new(project, Some(service), containers);
+                let collapsed = self.collapsed.contains(&group.key);
+                let indices = if collapsed { vec![] } else { group.containers.clone() };
+                rows.push(ContainerRow::Group { group, depth: 1, collapsed });
+                rows.extend(indices.into_iter().map(|index| self.container_row(index, 2)));
+            }
+        }
+        rows.extend(ungrouped.into_iter().map(|index| self.container_row(index, 0)));
+        self.items.set_items(rows);
+    }
+
+    fn container_row(&self, index: usize, depth: usize) -> ContainerRow {
+        // the daemon always sends an id, rows without one still need a key of their own
+        let id = self.containers[index].id.clone().unwrap_or_else(|| format!("#{}", index));
+        ContainerRow::Container { index, id, depth }
+    }
+
+    fn compare(&self, a: &ContainerSummaryInner, b: &ContainerSummaryInner) -> Ordering {
+        let cpu = |container: &ContainerSummaryInner| {
+            container.id.as_ref().and_then(|id| self.cpu.get(id)).copied().unwrap_or(0)
+        };
+        let ordering = match self.sort.key {
+            ContainerSort::Name => display_name(a).cmp(&display_name(b)),
+            ContainerSort::Created => a.created.cmp(&b.created),
+            ContainerSort::Status => state_rank(a).cmp(&state_rank(b)),
+            ContainerSort::Cpu => cpu(a).cmp(&cpu(b)),
+        };
+        // ties are broken by name and id so refreshes don't shuffle them
+        self.sort
+            .apply(ordering.then_with(|| display_name(a).cmp(&display_name(b))))
+            .then_with(|| a.id.cmp(&b.id))
+    }
+
+    pub fn sort(&self) -> SortOrder<ContainerSort> {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: SortOrder<ContainerSort>) {
+        self.sort = sort;
+        self.update_rows();
+    }
+
+    /// Takes a new cpu sample of a container into the order when sorting by it,
+    /// `None` once its stats stop. The rows are only sorted again when the whole
+    /// percent changes.
+    pub fn set_cpu(&mut self, id: &str, cpu: Option<f64>) {
+        let cpu = cpu.map(|cpu| cpu.round().max(0.0) as u64);
+        let changed = match cpu {
+            Some(cpu) => self.cpu.insert(id.to_string(), cpu) != Some(cpu),
+            None => self.cpu.remove(id).is_some(),
+        };
+        if changed && self.sort.key == ContainerSort::Cpu {
+            self.update_rows();
+        }
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.update_rows();
+    }
+
+    /// Folds the selected group away or unfolds it, returns false when no group is selected
+    pub fn toggle_collapsed(&mut self) -> bool {
+        let key = match self.selected_group() {
+            Some(group) => group.key.clone(),
+            None => return false,
+        };
+        if !self.collapsed.remove(&key) {
+            self.collapsed.insert(key);
+        }
+        self.update_rows();
+        true
+    }
+
+    /// Containers a mark applies to, the selected one or all of the selected group
+    fn selected_ids(&self) -> Vec<String> {
+        match self.items.selected() {
+            Some(ContainerRow::Container { index, .. }) => self.containers[*index].id.iter().cloned().collect(),
+            Some(ContainerRow::Group { group, .. }) => {
+                group.containers.iter().filter_map(|index| self.containers[*index].id.clone()).collect()
+            }
+            None => vec![],
+        }
+    }
+
+    /// Whether the container is shown, when it matches the filter and is up unless all are shown
+    fn is_listed(&self, container: &ContainerSummaryInner) -> bool {
+        (self.all || is_up(container)) && self.filter.matches(&searchable(container))
+    }
+
+    /// Ids of the containers that are shown, folded ones included
+    fn listed_ids(&self) -> Vec<String> {
+        self.containers
+            .iter()
+            .filter(|container| self.is_listed(container))
+            .filter_map(|container| container.id.clone())
+            .collect()
+    }
+
+    /// Marks the selected container or unmarks it and moves on to the next row.
+    /// On a group it marks all of its containers, or unmarks them when they all are.
+    pub fn toggle_mark(&mut self) {
+        let ids = self.selected_ids();
+        if ids.iter().all(|id| self.marked.contains(id)) {
+            ids.iter().for_each(|id| {
+                self.marked.remove(id);
+            });
+        } else {
+            self.marked.extend(ids);
+        }
+        self.items.forward(1);
+    }
+
+    /// Marks every container that is shown, keeping the marks of hidden ones
+    pub fn mark_all(&mut self) {
+        self.marked.extend(self.listed_ids());
+    }
+
+    /// Marks the containers shown that aren't and unmarks those that are, hidden ones stay as they are
+    pub fn invert_marks(&mut self) {
+        for id in self.listed_ids() {
+            if !self.marked.remove(&id) {
+                self.marked.insert(id);
+            }
+        }
+    }
+
+    /// How many of the marked containers the filter or the stopped ones being hidden hide
+    fn hidden_marks(&self) -> usize {
+        self.containers
+            .iter()
+            .filter(|container| !self.is_listed(container))
+            .filter(|container| container.id.as_ref().map_or(false, |id| self.marked.contains(id)))
+            .count()
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty()
+    }
+
+    /// The marked containers in the order of the sort, hidden ones included
+    pub fn marked(&self) -> Vec<&ContainerSummaryInner> {
+        let mut marked: Vec<&ContainerSummaryInner> = self.containers
+            .iter()
+            .filter(|container| container.id.as_ref().map_or(false, |id| self.marked.contains(id)))
+            .collect();
+        marked.sort_by(|a, b| self.compare(a, b));
+        marked
+    }
+
+    /// The selected container, `None` when a group is selected
+    pub fn selected(&self) -> Option<&ContainerSummaryInner> {
+        match self.items.selected()? {
+            ContainerRow::Container { index, .. } => self.containers.get(*index),
+            ContainerRow::Group { .. } => None,
+        }
+    }
+
+    pub fn selected_group(&self) -> Option<&ContainerGroup> {
+        match self.items.selected()? {
+            ContainerRow::Group { group, .. } => Some(group),
+            ContainerRow::Container { .. } => None,
+        }
+    }
+
+    /// Containers of a group of the list
+    pub fn group_containers(&self, group: &ContainerGroup) -> Vec<&ContainerSummaryInner> {
+        group.containers.iter().map(|index| &self.containers[*index]).collect()
+    }
+
+    pub fn list_mut(&mut self) -> &mut StatefulList<ContainerRow> {
+        &mut self.items
+    }
+
+    pub fn page_height(&self) -> usize {
+        self.height
+    }
+}
+
+/// Puts the mark in front of the first column
+fn with_mark<'a>(mut cells: Vec<Vec<Span<'a>>>, mark: &'static str) -> Vec<Vec<Span<'a>>> {
+    if let Some(first) = cells.first_mut() {
+        first.insert(0, Span::raw(mark));
+    }
+    cells
+}
+
+/// What the filter of the pane matches a container against
+fn searchable(container: &ContainerSummaryInner) -> Searchable<'_> {
+    let name = container.names.iter().flatten().next().map_or("", |name| name.trim_start_matches('/'));
+    let id = container.id.as_deref().unwrap_or_default();
+    let image = container.image.as_deref().unwrap_or_default();
+    let mut fields = vec![
+        ("name", name),
+        ("id", id),
+        ("image", image),
+        ("status", container.state.as_deref().unwrap_or_default()),
+        ("health", health(container).unwrap_or_default()),
+    ];
+    fields.extend(compose_project(container).map(|project| ("project", project)));
+    fields.extend(compose_service(container).map(|service| ("service", service)));
+    Searchable {
+        texts: vec![name, short_id(id), image],
+        fields,
+        labels: container.labels.iter().flatten().map(|(key, value)| (key.as_str(), value.as_str())).collect(),
+    }
+}
+
+/// Whether docker lists the container without `--all`, which paused and restarting ones count as
+fn is_up(container: &ContainerSummaryInner) -> bool {
+    matches!(container.state.as_deref(), Some("running") | Some("restarting") | Some("paused"))
+}
+
+/// Running containers first, then the ones that may come back, then those that are done
+fn state_rank(container: &ContainerSummaryInner) -> u8 {
+    match container.state.as_deref() {
+        Some("running") => 0,
+        Some("restarting") => 1,
+        Some("paused") => 2,
+        Some("created") => 3,
+        Some("exited") => 4,
+        Some("dead") => 5,
+        _ => 6,
+    }
+}
+
+/// First name of the container without the leading slash the api adds
+pub fn display_name(container: &ContainerSummaryInner) -> String {
+    container.names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|name| name.trim_start_matches('/').to_string())
+        .or_else(|| container.id.clone())
+        .unwrap_or_default()
+}
+
+/// Health check result, the api only reports it as part of the status, e.g. `Up 2 hours (healthy)`
+pub fn health(container: &ContainerSummaryInner) -> Option<&'static str> {
+    let status = container.status.as_deref()?;
+    if status.contains("(healthy)") {
+        Some("healthy")
+    } else if status.contains("(unhealthy)") {
+        Some("unhealthy")
+    } else if status.contains("(health: starting)") {
+        Some("starting")
+    } else {
+        None
+    }
+}
+
+pub fn compose_project(container: &ContainerSummaryInner) -> Option<&str> {
+    container.labels
+        .as_ref()
+        .and_then(|labels| labels.get(COMPOSE_PROJECT_LABEL))
+        .map(String::as_str)
+}
+
+pub fn compose_service(container: &ContainerSummaryInner) -> Option<&str> {
+    container.labels
+        .as_ref()
+        .and_then(|labels| labels.get(COMPOSE_SERVICE_LABEL))
+        .map(String::as_str)
+}
+
+/// Ports published on the host, e.g. `8080->80/tcp`. The daemon lists a
+/// binding once per address family so duplicates are dropped.
+fn published_ports(container: &ContainerSummaryInner) -> String {
+    let mut ports: Vec<String> = container.ports
+        .iter()
+        .flatten()
+        .filter_map(|port| {
+            port.public_port.map(|public| {
+                let typ = port.typ.map(|typ| typ.to_string()).unwrap_or_default();
+                format!("{}->{}/{}", public, port.private_port, typ)
+            })
+        })
+        .collect();
+    ports.sort();
+    ports.dedup();
+    ports.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::service::{Port, PortTypeEnum};
+
+    fn port(private_port: i64, public_port: Option<i64>, typ: PortTypeEnum) -> Port {
+        Port { private_port, public_port, typ: Some(typ), ..Default::default() }
+    }
+
+    #[test]
+    fn published_ports_lists_each_published_port_once() {
+        let container = ContainerSummaryInner {
+            ports: Some(vec![
+                port(80, Some(8080), PortTypeEnum::TCP),
+                // the same port published on ipv6 too
+                port(80, Some(8080), PortTypeEnum::TCP),
+                port(53, Some(5353), PortTypeEnum::UDP),
+                port(443, None, PortTypeEnum::TCP),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(published_ports(&container), "5353->53/udp, 8080->80/tcp");
+    }
+
+    #[test]
+    fn published_ports_is_empty_without_ports() {
+        assert_eq!(published_ports(&ContainerSummaryInner::default()), "");
+    }
+}
@@ -1,33 +1,72 @@
 use anyhow::Error;
 use bollard::models::ImageSummary;
-use bollard::service::ContainerSummaryInner;
-use itertools::Itertools;
 use tui::backend::Backend;
 use tui::Frame;
 use tui::layout::{Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, List, ListItem};
+use serde::{Deserialize, Serialize};
 
 use crate::components::{MutableDrawableComponent};
-use crate::components::main_app::MainApp;
-use crate::components::util::StatefulList;
+use crate::components::main_app::{MainApp, Pane};
+use crate::components::util::{format_age, format_bytes, hidden_marks_note, short_id, Keyed, SortKey, SortOrder, StatefulList};
+use crate::components::util::filter::{Filter, Searchable};
+
+const UNTAGGED: &str = "<none>:<none>";
+
+/// What the images are sorted by
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ImageSort {
+    Created,
+    Size,
+}
+
+impl SortKey for ImageSort {
+    const ALL: &'static [ImageSort] = &[ImageSort::Created, ImageSort::Size];
+
+    fn name(self) -> &'static str {
+        match self {
+            ImageSort::Created => "created",
+            ImageSort::Size => "size",
+        }
+    }
+
+    fn descending(self) -> bool {
+        true
+    }
+}
 
 pub struct Images {
-    selected: usize,
     items: StatefulList<ImageSummary>,
+    all: Vec<ImageSummary>,
+    show_untagged: bool,
+    filter: Filter,
+    sort: SortOrder<ImageSort>,
+    height: usize,
+}
+
+impl Keyed for ImageSummary {
+    fn key(&self) -> &str {
+        &self.id
+    }
 }
 
 impl MutableDrawableComponent for Images {
     fn draw<B: Backend>(&mut self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
-        let mut names = Images::filter_names(app);
-        names.dedup();
+        self.height = rect.height.saturating_sub(2) as usize;
 
-        let items = Images::map_to_list_items(&names);
+        let items = Images::map_to_list_items(&self.items.items, &self.items.mark_symbols(), &self.filter, app);
 
-        //TODO items is 38 here :/
+        let focused = app.is_focused(Pane::Images);
+        let title = if self.show_untagged { "Images (all)" } else { "Images" };
+        let title = format!("{} {}", title, self.sort.label());
+        let title = format!("{}{}", self.filter.title(&title), hidden_marks_note(self.items.hidden_marks()));
         let items = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Images"))
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.block(focused))
+                .title(Span::styled(title, app.theme.title(focused))))
             .highlight_style(
                 Style::default()
                     .bg(Color::LightGreen)
@@ -41,46 +80,129 @@ impl MutableDrawableComponent for Images {
 
 
 impl Images {
-    pub fn new() -> Images {
+    pub fn new(sort: SortOrder<ImageSort>) -> Images {
         Images {
-            selected: 0,
             items: StatefulList::new(),
+            all: vec![],
+            show_untagged: false,
+            filter: Filter::default(),
+            sort,
+            height: 0,
         }
     }
 
-    fn filter_names(app: &MainApp) -> Vec<String> {
-        let names: Vec<String> = app.images.clone()
+    pub fn set_items(&mut self, images: Vec<ImageSummary>) {
+        self.all = images;
+        self.items.retain_marks(&self.all);
+        self.apply_filter();
+    }
+
+    /// Keeps the images matching the filter. Dangling and intermediate images
+    /// have no tags, they are hidden unless asked for
+    fn apply_filter(&mut self) {
+        let show_untagged = self.show_untagged;
+        let filter = &self.filter;
+        let mut images: Vec<ImageSummary> = self.all
             .iter()
-            .map(|i| {
-                let summary = i.clone();
-                let images = summary.labels.get_key_value("name");
-
-                match images {
-                    None => String::new(),
-                    Some(value) => {
-                        value.1.clone()
-                    }
-                }
-            })
-            .filter(|name| !name.is_empty())
+            .filter(|image| show_untagged || !Images::is_untagged(image))
+            .filter(|image| filter.matches(&Images::searchable(image)))
+            .cloned()
             .collect();
-        names
+        let sort = self.sort;
+        images.sort_by(|a, b| {
+            let ordering = match sort.key {
+                ImageSort::Created => a.created.cmp(&b.created),
+                ImageSort::Size => a.size.cmp(&b.size),
+            };
+            // the id breaks ties so refreshes don't shuffle them
+            sort.apply(ordering).then_with(|| a.id.cmp(&b.id))
+        });
+        self.items.set_items(images);
+    }
+
+    pub fn sort(&self) -> SortOrder<ImageSort> {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: SortOrder<ImageSort>) {
+        self.sort = sort;
+        self.apply_filter();
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
     }
 
-    fn map_to_list_items(names: &Vec<String>) -> Vec<ListItem> {
-        let items: Vec<ListItem> = names
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.apply_filter();
+    }
+
+    pub fn toggle_untagged(&mut self) {
+        self.show_untagged = !self.show_untagged;
+        self.apply_filter();
+    }
+
+    pub fn selected(&self) -> Option<&ImageSummary> {
+        self.items.selected()
+    }
+
+    pub fn list_mut(&mut self) -> &mut StatefulList<ImageSummary> {
+        &mut self.items
+    }
+
+    pub fn has_marks(&self) -> bool {
+        self.items.has_marks()
+    }
+
+    /// The marked images, those the filter hides included
+    pub fn marked(&self) -> Vec<&ImageSummary> {
+        self.items.marked(&self.all)
+    }
+
+    pub fn page_height(&self) -> usize {
+        self.height
+    }
+
+    fn is_untagged(image: &ImageSummary) -> bool {
+        image.repo_tags.iter().all(|tag| tag == UNTAGGED)
+    }
+
+    /// What the filter of the pane matches an image against, any of its tags is its name
+    fn searchable(image: &ImageSummary) -> Searchable<'_> {
+        let tags = image.repo_tags.iter().filter(|tag| tag.as_str() != UNTAGGED);
+        let mut texts: Vec<&str> = tags.clone().map(String::as_str).collect();
+        texts.push(short_id(&image.id));
+        let mut fields: Vec<(&str, &str)> = tags.map(|tag| ("name", tag.as_str())).collect();
+        fields.push(("id", image.id.trim_start_matches("sha256:")));
+        Searchable {
+            texts,
+            fields,
+            labels: image.labels.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect(),
+        }
+    }
+
+    fn map_to_list_items<'a>(images: &'a [ImageSummary], marks: &[&'static str], filter: &Filter, app: &MainApp) -> Vec<ListItem<'a>> {
+        images
             .iter()
-            .map(|name| {
-                vec![(Spans::from(Span::styled(
-                    name,
-                    Style::default().add_modifier(Modifier::ITALIC),
-                )))]
+            .zip(marks)
+            .map(|(image, mark)| {
+                let tags = if Images::is_untagged(image) {
+                    UNTAGGED.to_string()
+                } else {
+                    image.repo_tags.join(", ")
+                };
+                let details = format!(" {} {}", format_bytes(image.size.max(0) as u64), format_age(image.created));
+                let highlight = app.theme.filter_match();
+                let mut id = filter.spans(short_id(&image.id).to_string(), app.theme.text(false, false), highlight);
+                id.push(Span::styled(details, app.theme.text(false, false)));
+                let mut tags = filter.spans(tags, Style::default().add_modifier(Modifier::ITALIC), highlight);
+                tags.insert(0, Span::raw(*mark));
+                ListItem::new(vec![
+                    Spans::from(tags),
+                    Spans::from(id),
+                ]).style(Style::default().fg(Color::Red))
             })
-            .map(|mut lines| {
-                lines.dedup();
-                ListItem::new(lines).style(Style::default().fg(Color::Red))
-            })
-            .collect();
-        items
+            .collect()
     }
 }
@@ -0,0 +1,371 @@
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::Error;
+use bollard::container::LogOutput;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Alignment, Rect};
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::{MainApp, Pane};
+
+/// Colours of the prefixes telling interleaved containers apart
+const PREFIX_COLORS: [Color; 6] = [Color::Cyan, Color::Yellow, Color::Green, Color::Magenta, Color::Blue, Color::LightRed];
+
+/// A container whose output goes to the logs pane, lines of several
+/// containers followed together are prefixed to tell them apart
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogStream {
+    pub id: String,
+    pub prefix: Option<String>,
+}
+
+pub struct LogLine {
+    stderr: bool,
+    timestamp: Option<String>,
+    prefix: Option<usize>,
+    text: String,
+}
+
+impl LogLine {
+    /// Splits off the RFC3339 timestamp the daemon prepends when asked for timestamps
+    fn parse(line: &str, stderr: bool) -> LogLine {
+        let (timestamp, text) = match line.find(' ') {
+            Some(i) if line[..i].ends_with('Z') && line[..i].contains('T') => {
+                (Some(line[..i].to_string()), line[i + 1..].to_string())
+            }
+            _ => (None, line.to_string()),
+        };
+        LogLine {
+            stderr,
+            timestamp,
+            prefix: None,
+            text: text.trim_end_matches('\r').to_string(),
+        }
+    }
+
+    fn message(text: &str) -> LogLine {
+        LogLine {
+            stderr: false,
+            timestamp: None,
+            prefix: None,
+            text: text.to_string(),
+        }
+    }
+}
+
+/// Joins the chunks of a log stream back into lines, the daemon doesn't frame
+/// output of tty containers on line boundaries.
+#[derive(Default)]
+pub struct LineSplitter {
+    stdout: String,
+    stderr: String,
+}
+
+impl LineSplitter {
+    pub fn push(&mut self, output: LogOutput) -> Vec<LogLine> {
+        let stderr = matches!(output, LogOutput::StdErr { .. });
+        let pending = if stderr { &mut self.stderr } else { &mut self.stdout };
+        pending.push_str(&output.to_string());
+
+        let mut lines = vec![];
+        while let Some(i) = pending.find('\n') {
+            let line: String = pending.drain(..=i).collect();
+            lines.push(LogLine::parse(line.trim_end_matches('\n'), stderr));
+        }
+        lines
+    }
+
+    /// Whatever is left once the stream ended
+    pub fn flush(&mut self) -> Vec<LogLine> {
+        let mut lines = vec![];
+        if !self.stdout.is_empty() {
+            lines.push(LogLine::parse(&self.stdout, false));
+        }
+        if !self.stderr.is_empty() {
+            lines.push(LogLine::parse(&self.stderr, true));
+        }
+        self.stdout.clear();
+        self.stderr.clear();
+        lines
+    }
+}
+
+/// Whether `line` goes before a line logged at `timestamp`, lines without one
+/// are messages of this ui and stay where they were added
+fn is_before(line: &LogLine, timestamp: &str) -> bool {
+    line.timestamp.as_deref().map_or(true, |other| other <= timestamp)
+}
+
+/// Bounded scrollback of the followed containers' output
+pub struct Logs {
+    streams: Vec<LogStream>,
+    /// Streams the daemon stopped sending, e.g. because their container exited
+    ended: HashSet<String>,
+    name: String,
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    /// Number of lines scrolled up from the newest one
+    offset: usize,
+    follow: bool,
+    timestamps: bool,
+}
+
+impl Logs {
+    pub fn new(capacity: usize, timestamps: bool) -> Logs {
+        Logs {
+            streams: vec![],
+            ended: HashSet::new(),
+            name: String::new(),
+            lines: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            offset: 0,
+            follow: true,
+            timestamps,
+        }
+    }
+
+    pub fn streams(&self) -> &[LogStream] {
+        &self.streams
+    }
+
+    pub fn has_ended(&self, container_id: &str) -> bool {
+        self.ended.contains(container_id)
+    }
+
+    /// Starts over for other containers, lines of the previous ones still in flight are dropped
+    pub fn reset(&mut self, streams: Vec<LogStream>, name: String) {
+        self.streams = streams;
+        self.name = name;
+        self.lines.clear();
+        self.offset = 0;
+        self.follow = true;
+        self.ended.clear();
+    }
+
+    pub fn push(&mut self, container_id: &str, lines: Vec<LogLine>) {
+        let stream = match self.streams.iter().position(|stream| stream.id == container_id) {
+            Some(stream) => stream,
+            None => return,
+        };
+        let prefixed = self.streams[stream].prefix.is_some();
+        for mut line in lines {
+            if self.lines.len() == self.capacity {
+                self.lines.pop_front();
+            }
+            if prefixed {
+                line.prefix = Some(stream);
+            }
+            // streams of several containers arrive in chunks, the timestamps put them back in order.
+            // The lines are kept sorted so a late line finds its place by bisecting, and the
+            // usual one, newer than all the others, goes to the back without searching.
+            let position = match &line.timestamp {
+                Some(timestamp) if self.lines.back().map_or(false, |last| !is_before(last, timestamp)) => {
+                    self.lines.partition_point(|other| is_before(other, timestamp))
+                }
+                _ => self.lines.len(),
+            };
+            // a paused view keeps showing the same lines while new ones arrive below them
+            if !self.follow && position >= self.lines.len() - self.offset.min(self.lines.len()) {
+                self.offset += 1;
+            }
+            self.lines.insert(position, line);
+        }
+        self.offset = self.offset.min(self.lines.len());
+    }
+
+    pub fn end(&mut self, container_id: &str, reason: &str) {
+        if self.streams.iter().any(|stream| stream.id == container_id) {
+            self.push(container_id, vec![LogLine::message(reason)]);
+            self.ended.insert(container_id.to_string());
+        }
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.follow = false;
+        self.offset = (self.offset + lines).min(self.lines.len());
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.offset = self.offset.saturating_sub(lines);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_up(self.lines.len());
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.offset = 0;
+    }
+
+    pub fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+        if self.follow {
+            self.offset = 0;
+        }
+    }
+
+    pub fn toggle_timestamps(&mut self) {
+        self.timestamps = !self.timestamps;
+    }
+
+    fn title(&self) -> String {
+        let mut title = String::from("Logs");
+        if !self.name.is_empty() {
+            title.push_str(&format!(" - {}", self.name));
+        }
+        if !self.follow {
+            title.push_str(" [paused]");
+        }
+        title
+    }
+}
+
+impl DrawableComponent for Logs {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let height = rect.height.saturating_sub(2) as usize;
+        let end = self.lines.len() - self.offset.min(self.lines.len());
+        let start = end.saturating_sub(height);
+        let prefix_width = self.streams
+            .iter()
+            .filter_map(|stream| stream.prefix.as_ref().map(String::len))
+            .max()
+            .unwrap_or(0);
+
+        let text: Vec<Spans> = self.lines
+            .range(start..end)
+            .map(|line| {
+                let mut spans = vec![];
+                if let Some(stream) = line.prefix {
+                    let prefix = self.streams[stream].prefix.as_deref().unwrap_or_default();
+                    let color = PREFIX_COLORS[stream % PREFIX_COLORS.len()];
+                    spans.push(Span::styled(format!("{:<1$} | ", prefix, prefix_width), Style::default().fg(color)));
+                }
+                if self.timestamps {
+                    if let Some(timestamp) = &line.timestamp {
+                        spans.push(Span::styled(format!("{} ", timestamp), app.theme.commit_time(false)));
+                    }
+                }
+                let style = if line.stderr {
+                    app.theme.text_danger()
+                } else {
+                    app.theme.text(true, false)
+                };
+                spans.push(Span::styled(line.text.as_str(), style));
+                Spans::from(spans)
+            })
+            .collect();
+
+        let focused = app.is_focused(Pane::Logs);
+        f.render_widget(
+            Paragraph::new(text)
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(app.theme.block(focused))
+                    .title(Span::styled(self.title(), app.theme.title(focused))))
+                .alignment(Alignment::Left),
+            rect);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdout(text: &'static str) -> LogOutput {
+        LogOutput::StdOut { message: text.into() }
+    }
+
+    fn texts(logs: &Logs) -> Vec<&str> {
+        logs.lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    fn followed(capacity: usize) -> Logs {
+        let mut logs = Logs::new(capacity, false);
+        let streams = vec![
+            LogStream { id: "a".to_string(), prefix: Some("web".to_string()) },
+            LogStream { id: "b".to_string(), prefix: Some("db".to_string()) },
+        ];
+        logs.reset(streams, "shop".to_string());
+        logs
+    }
+
+    #[test]
+    fn line_splitter_joins_chunks_into_lines() {
+        let mut splitter = LineSplitter::default();
+        assert!(splitter.push(stdout("hel")).is_empty());
+        let lines = splitter.push(stdout("lo\r\nworld\nagain"));
+        let texts: Vec<&str> = lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, vec!["hello", "world"]);
+        let rest = splitter.flush();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].text, "again");
+        assert!(splitter.flush().is_empty());
+    }
+
+    #[test]
+    fn line_splitter_keeps_stdout_and_stderr_apart() {
+        let mut splitter = LineSplitter::default();
+        assert!(splitter.push(stdout("out")).is_empty());
+        let lines = splitter.push(LogOutput::StdErr { message: "err\n".into() });
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].stderr);
+        assert_eq!(lines[0].text, "err");
+        let lines = splitter.push(stdout("put\n"));
+        assert!(!lines[0].stderr);
+        assert_eq!(lines[0].text, "output");
+    }
+
+    #[test]
+    fn parse_splits_off_the_timestamp() {
+        let line = LogLine::parse("2020-10-01T10:00:00.000000001Z started", false);
+        assert_eq!(line.timestamp.as_deref(), Some("2020-10-01T10:00:00.000000001Z"));
+        assert_eq!(line.text, "started");
+        let line = LogLine::parse("no timestamp here\r", true);
+        assert_eq!(line.timestamp, None);
+        assert_eq!(line.text, "no timestamp here");
+        assert!(line.stderr);
+    }
+
+    #[test]
+    fn push_orders_the_lines_of_two_streams_by_timestamp() {
+        let mut logs = followed(10);
+        let parse = |line| LogLine::parse(line, false);
+        logs.push("a", vec![parse("2020-10-01T10:00:01Z a1"), parse("2020-10-01T10:00:03Z a3")]);
+        logs.push("b", vec![parse("2020-10-01T10:00:02Z b2"), parse("2020-10-01T10:00:04Z b4")]);
+        logs.push("a", vec![parse("2020-10-01T10:00:00Z a0")]);
+        assert_eq!(texts(&logs), vec!["a0", "a1", "b2", "a3", "b4"]);
+        assert_eq!(logs.lines[2].prefix, Some(1));
+    }
+
+    #[test]
+    fn push_drops_the_oldest_lines_beyond_the_capacity() {
+        let mut logs = followed(2);
+        let lines = vec![LogLine::message("one"), LogLine::message("two"), LogLine::message("three")];
+        logs.push("a", lines);
+        assert_eq!(texts(&logs), vec!["two", "three"]);
+    }
+
+    #[test]
+    fn paused_view_stays_on_the_same_lines() {
+        let mut logs = followed(10);
+        let parse = |line| LogLine::parse(line, false);
+        let bottom = |logs: &Logs| logs.lines[logs.lines.len() - logs.offset - 1].text.clone();
+        logs.push("a", vec![parse("2020-10-01T10:00:01Z a1"), parse("2020-10-01T10:00:05Z a5")]);
+        logs.scroll_up(1);
+        assert_eq!(bottom(&logs), "a1");
+        // below the view, the view moves up with it
+        logs.push("a", vec![parse("2020-10-01T10:00:06Z a6")]);
+        logs.push("b", vec![parse("2020-10-01T10:00:02Z b2")]);
+        assert_eq!(logs.offset, 3);
+        assert_eq!(bottom(&logs), "a1");
+        // above the bottom of the view, the offset stays
+        logs.push("b", vec![parse("2020-10-01T10:00:00Z b0")]);
+        assert_eq!(logs.offset, 3);
+        assert_eq!(bottom(&logs), "a1");
+    }
+}
@@ -1,5 +1,7 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
 use anyhow::Error;
@@ -16,56 +18,374 @@ use crate::components::util::TabsState;
 use crate::style::{SharedTheme, Theme};
 use crate::components::{DrawableComponent};
 use crate::components::tabs::get_tabs;
-use bollard::service::{ContainerSummaryInner, ImageSummary, Volume};
-use std::sync::mpsc::Sender;
+use bollard::service::{ContainerSummaryInner, ImageSummary, Network, Volume};
+use tokio::sync::mpsc::UnboundedSender;
 use crate::docker;
-use crate::docker::IOEvent;
+use crate::docker::{BulkAction, ConnectionState, ContainerAction, ImageAction, InspectTarget, IOEvent, NetworkAction, VolumeAction};
+use crate::components::containers::{compose_service, display_name, ContainerSort, Containers};
+use crate::components::images::Images;
+use crate::components::volumes::Volumes;
+use crate::components::tabs::Tab;
+use crate::components::util::{short_id, Keyed, StatefulList};
+use crate::components::util::filter::Filter;
+use crate::components::logs::{LogStream, Logs};
+use crate::components::stats::{ContainerStats, StatsTable};
+use crate::components::version::VersionInfo;
+use crate::clipboard;
+use std::collections::HashMap;
+use crate::options::Options;
+use crate::terminal::Session;
+use crate::components::popups::{ChoicePopup, ConfirmPopup, Field, Flag, FormPopup, InputPopup, MessagePopup, Popup, PopupAction, PullPopup, parse_pairs};
+use crate::components::networks::{Networks, BUILTIN_NETWORKS};
+use tui::widgets::Paragraph;
 
 pub struct MainApp {
     should_quit: bool,
     tab_state: TabsState,
-    theme: SharedTheme,
+    pub theme: SharedTheme,
     selected_tab: usize,
     selected_pane: Pane,
     pub containers: Vec<ContainerSummaryInner>,
+    pub container_list: RefCell<Containers>,
     pub images: Vec<ImageSummary>,
+    pub image_list: RefCell<Images>,
     pub volumes: Vec<Volume>,
-    tx: Sender<docker::IOEvent>
+    pub volume_list: RefCell<Volumes>,
+    pub networks: Vec<Network>,
+    pub network_list: RefCell<Networks>,
+    pub logs: Logs,
+    pub stats: HashMap<String, ContainerStats>,
+    pub stats_table: RefCell<StatsTable>,
+    pub version: Option<VersionInfo>,
+    pub options: Options,
+    /// Whether stopped containers are listed, `--all` sets it for one run without changing the saved option
+    pub all_containers: bool,
+    pub connection: ConnectionState,
+    pub events_live: bool,
+    /// When the volumes and networks were last polled without the event stream
+    slow_polled: Option<Instant>,
+    pub popup: Option<Popup>,
+    pub notice: Option<String>,
+    /// Keys go to the filter of the focused pane while it is typed
+    filtering: bool,
+    /// A shell or attached process waiting for the ui to hand the terminal over
+    pub session: Option<Session>,
+    host: String,
+    tx: UnboundedSender<docker::IOEvent>
 }
 
-enum Pane {
+const KILL_SIGNALS: [&str; 7] = ["SIGKILL", "SIGTERM", "SIGINT", "SIGHUP", "SIGQUIT", "SIGUSR1", "SIGUSR2"];
+
+const PRUNE_DANGLING: &str = "Dangling images";
+const PRUNE_UNUSED: &str = "All images without a container";
+
+/// How often the volumes and networks are polled while the event stream is down,
+/// listing them takes a disk usage scan and a request per network
+const SLOW_POLL: Duration = Duration::from_secs(5);
+
+/// Switching tabs moved to the arrow keys when PageUp and PageDown started paging the lists
+const TAB_HINTS: &str = "←/→: tabs";
+
+/// Lines moved per scroll key in the logs pane
+const LOGS_SCROLL: usize = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pane {
     Containers,
     Images,
     Volumes,
+    Networks,
     Logs
 }
 
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Containers => Pane::Images,
+            Pane::Images => Pane::Volumes,
+            Pane::Volumes => Pane::Networks,
+            Pane::Networks => Pane::Logs,
+            Pane::Logs => Pane::Containers,
+        }
+    }
+
+    fn previous(self) -> Pane {
+        match self {
+            Pane::Containers => Pane::Logs,
+            Pane::Images => Pane::Containers,
+            Pane::Volumes => Pane::Images,
+            Pane::Networks => Pane::Volumes,
+            Pane::Logs => Pane::Networks,
+        }
+    }
+}
+
+/// Applies a navigation key to a list, returns false when the key isn't one
+fn navigate<T>(list: &mut StatefulList<T>, key: Key, page: usize) -> bool {
+    match key {
+        Key::Down => list.next(),
+        Key::Up => list.previous(),
+        Key::Home => list.first(),
+        Key::End => list.last(),
+        Key::PageDown => list.forward(page.max(1)),
+        Key::PageUp => list.backward(page.max(1)),
+        _ => return false,
+    }
+    true
+}
+
+/// Id, name and state of a container, what the actions on several containers need
+fn container_target(container: &ContainerSummaryInner) -> (String, String, String) {
+    (
+        container.id.clone().unwrap_or_default(),
+        display_name(container),
+        container.state.clone().unwrap_or_default(),
+    )
+}
+
+/// Id, name and tags of an image, the name is its first tag or its short id when it has none
+fn image_target(image: &ImageSummary) -> (String, String, Vec<String>) {
+    let tags: Vec<String> = image.repo_tags
+        .iter()
+        .filter(|tag| tag.as_str() != "<none>:<none>")
+        .cloned()
+        .collect();
+    let name = tags.first().cloned().unwrap_or_else(|| short_id(&image.id).to_string());
+    (image.id.clone(), name, tags)
+}
+
+/// Applies a mark key to a list, see `MainApp::on_mark_key`
+fn mark<T: Keyed>(list: &mut StatefulList<T>, c: char) {
+    match c {
+        ' ' => list.toggle_mark(),
+        '*' => list.mark_all(),
+        '~' => list.invert_marks(),
+        _ => {}
+    }
+}
+
+/// One action per container, given as id, name and state, `action` says what to do to it, if anything
+fn container_actions(
+    containers: &[(String, String, String)],
+    action: impl Fn(&str) -> Option<ContainerAction>,
+) -> Vec<BulkAction> {
+    containers
+        .iter()
+        .filter_map(|(id, name, state)| {
+            action(state).map(|action| BulkAction::Container { id: id.clone(), name: name.clone(), action })
+        })
+        .collect()
+}
+
 impl MainApp {
-    pub fn new(tx: Sender<docker::IOEvent>) -> MainApp {
+    pub fn new(tx: UnboundedSender<docker::IOEvent>, options: Options, host: String, all_containers: bool) -> MainApp {
         let theme = Arc::new(Theme::init());
 
         let tabs = get_tabs();
 
-        MainApp {
+        let mut app = MainApp {
             selected_pane: Pane::Containers,
             should_quit: false,
             tab_state: TabsState::new(tabs), //Build tabs from dynamic list TODO
             theme,
             selected_tab: 0,
             containers: vec![],
+            container_list: RefCell::new(Containers::new(options.container_sort)),
             images: vec![],
+            image_list: RefCell::new(Images::new(options.image_sort)),
             volumes: vec![],
+            volume_list: RefCell::new(Volumes::new(options.volume_sort)),
+            networks: vec![],
+            network_list: RefCell::new(Networks::new()),
+            logs: Logs::new(options.logs_capacity, options.logs_timestamps),
+            stats: HashMap::new(),
+            stats_table: RefCell::new(StatsTable::new()),
+            version: None,
+            all_containers: all_containers || options.all_containers,
+            options,
+            connection: ConnectionState::Connected,
+            events_live: false,
+            slow_polled: None,
+            popup: None,
+            notice: None,
+            filtering: false,
+            session: None,
+            host,
             tx
+        };
+        // sorting by cpu needs the stats from the start
+        if app.options.container_sort.key == ContainerSort::Cpu {
+            app.watch_stats();
         }
+        app
     }
 
     fn update(&mut self) {
+        // the docker event stream drives the refreshes while it is connected
+        if self.events_live {
+            return;
+        }
         if let Err(err) = self.tx.send(IOEvent::RefreshImages) {
             log::error!("Failed to send the message to refresh images, {}", err)
         }
         if let Err(err) = self.tx.send(IOEvent::RefreshContainers) {
             log::error!("Failed to send the message to refresh containers, {}", err)
         }
+        if self.slow_polled.map_or(false, |polled| polled.elapsed() < SLOW_POLL) {
+            return;
+        }
+        self.slow_polled = Some(Instant::now());
+        if let Err(err) = self.tx.send(IOEvent::RefreshVolumes) {
+            log::error!("Failed to send the message to refresh volumes, {}", err)
+        }
+        if let Err(err) = self.tx.send(IOEvent::RefreshNetworks) {
+            log::error!("Failed to send the message to refresh networks, {}", err)
+        }
+    }
+
+    pub fn is_focused(&self, pane: Pane) -> bool {
+        self.selected_pane == pane
+    }
+
+    fn on_containers_tab(&self) -> bool {
+        matches!(self.tab_state.get_current_tab(), Tab::Containers)
+    }
+
+    /// Stats are streamed for the stats tab and for sorting the containers by cpu
+    fn watch_stats(&mut self) {
+        let watch_stats = matches!(self.tab_state.get_current_tab(), Tab::Stats)
+            || self.options.container_sort.key == ContainerSort::Cpu;
+        self.send(IOEvent::WatchStats(watch_stats));
+    }
+
+    fn on_tab_changed(&mut self) {
+        self.selected_tab = self.tab_state.index;
+        self.watch_stats();
+        if matches!(self.tab_state.get_current_tab(), Tab::Version) {
+            self.send(IOEvent::RefreshVersion);
+        }
+    }
+
+    fn copy_version(&mut self) {
+        let text = match &self.version {
+            Some(version) => version.to_text(),
+            None => return,
+        };
+        match clipboard::copy_string(&text) {
+            Ok(_) => self.notice = Some("Copied the version information to the clipboard".to_string()),
+            Err(err) => self.popup = Some(Popup::Message(MessagePopup::error("Copy failed", err.to_string()))),
+        }
+    }
+
+    pub fn set_images(&mut self, images: Vec<ImageSummary>) {
+        self.image_list.get_mut().set_items(images.clone());
+        self.images = images;
+    }
+
+    pub fn set_volumes(&mut self, volumes: Vec<Volume>) {
+        self.volume_list.get_mut().set_items(volumes.clone());
+        self.volumes = volumes;
+    }
+
+    pub fn set_networks(&mut self, networks: Vec<Network>) {
+        self.network_list.get_mut().set_items(networks.clone());
+        self.networks = networks;
+    }
+
+    /// Moves the selection of the focused pane, or scrolls it for the logs
+    fn on_navigation_key(&mut self, key: Key) {
+        match self.selected_pane {
+            Pane::Containers => {
+                let containers = self.container_list.get_mut();
+                let page = containers.page_height();
+                if navigate(containers.list_mut(), key, page) {
+                    self.follow_selected_logs();
+                }
+            }
+            Pane::Images => {
+                let images = self.image_list.get_mut();
+                let page = images.page_height();
+                navigate(images.list_mut(), key, page);
+            }
+            Pane::Volumes => {
+                let volumes = self.volume_list.get_mut();
+                let page = volumes.page_height();
+                navigate(volumes.list_mut(), key, page);
+            }
+            Pane::Networks => {
+                let networks = self.network_list.get_mut();
+                let page = networks.page_height();
+                navigate(networks.list_mut(), key, page);
+            }
+            Pane::Logs => match key {
+                Key::Up => self.logs.scroll_up(1),
+                Key::Down => self.logs.scroll_down(1),
+                Key::PageUp => self.logs.scroll_up(LOGS_SCROLL),
+                Key::PageDown => self.logs.scroll_down(LOGS_SCROLL),
+                Key::Home => self.logs.scroll_to_top(),
+                Key::End => self.logs.scroll_to_bottom(),
+                _ => {}
+            },
+        }
+    }
+
+    pub fn set_containers(&mut self, containers: Vec<ContainerSummaryInner>) {
+        self.containers = containers;
+        self.list_containers();
+    }
+
+    /// Points the logs pane at the selected container whenever the selection moved, a
+    /// selected project or service interleaves the logs of all its containers
+    fn follow_selected_logs(&mut self) {
+        let list = self.container_list.get_mut();
+        let (containers, name, grouped) = match (list.selected(), list.selected_group()) {
+            (Some(container), _) => (vec![container], display_name(container), false),
+            (None, Some(group)) => (list.group_containers(group), group.label(), true),
+            (None, None) => (vec![], String::new(), false),
+        };
+        let streams: Vec<LogStream> = containers
+            .iter()
+            .map(|container| {
+                // replicas of a service share its name, their container names tell them apart
+                let service = compose_service(container);
+                let replicas = containers.iter().filter(|other| compose_service(other) == service).count();
+                let prefix = match service {
+                    Some(service) if replicas == 1 => service.to_string(),
+                    _ => display_name(container),
+                };
+                LogStream { id: container.id.clone().unwrap_or_default(), prefix: Some(prefix).filter(|_| grouped) }
+            })
+            .collect();
+
+        // a restarted container gets a fresh stream once the old one ended
+        let logs = &self.logs;
+        let restarted = containers.iter().any(|container| {
+            container.state.as_deref() == Some("running")
+                && logs.has_ended(container.id.as_deref().unwrap_or_default())
+        });
+        if streams.as_slice() == self.logs.streams() && !restarted {
+            return;
+        }
+        self.logs.reset(streams.clone(), name);
+        let tail = self.options.logs_tail;
+        self.send(IOEvent::FollowLogs { streams, tail });
+    }
+
+    fn on_logs_key(&mut self, c: char) -> bool {
+        match c {
+            'f' => self.logs.toggle_follow(),
+            't' => self.logs.toggle_timestamps(),
+            '[' => self.logs.scroll_up(LOGS_SCROLL),
+            ']' => self.logs.scroll_down(LOGS_SCROLL),
+            _ => return false,
+        }
+        true
+    }
+
+    fn send(&mut self, event: IOEvent) {
+        if let Err(err) = self.tx.send(event) {
+            log::error!("Failed to send the message to the IO worker, {}", err)
+        }
     }
 
     pub fn on_key(&mut self, c: char) {
@@ -74,28 +394,728 @@ impl MainApp {
                 self.should_quit = true;
 
             }
+            'y' if matches!(self.tab_state.get_current_tab(), Tab::Version) => self.copy_version(),
+            '\n' if matches!(self.tab_state.get_current_tab(), Tab::Stats) => {
+                if let Some(id) = self.stats_table.get_mut().selected_id() {
+                    let target = InspectTarget::Container(id.to_string());
+                    self.send(IOEvent::Inspect(target));
+                }
+            }
+            _ if !self.on_containers_tab() => {}
+            '\n' => self.inspect_selected(),
+            '/' if self.pane_filter().is_some() => self.filtering = true,
+            _ => {
+                // the logs keys act on the selected container's logs
+                let logs_pane = matches!(self.selected_pane, Pane::Containers | Pane::Logs);
+                let handled = (logs_pane && self.on_logs_key(c)) || self.on_sort_key(c) || self.on_mark_key(c);
+                if !handled {
+                    match self.selected_pane {
+                        Pane::Containers => self.on_container_key(c),
+                        Pane::Images => self.on_image_key(c),
+                        Pane::Volumes => self.on_volume_key(c),
+                        Pane::Networks => self.on_network_key(c),
+                        Pane::Logs => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// `o` sorts the focused pane by the next key, `O` reverses the order. Returns false when the pane can't be sorted
+    fn on_sort_key(&mut self, c: char) -> bool {
+        match (self.selected_pane, c) {
+            (Pane::Containers, 'o' | 'O') => {
+                let sort = self.options.container_sort;
+                self.options.container_sort = if c == 'o' { sort.next_key() } else { sort.reversed() };
+                self.container_list.get_mut().set_sort(self.options.container_sort);
+                self.follow_selected_logs();
+                self.watch_stats();
+            }
+            (Pane::Images, 'o' | 'O') => {
+                let sort = self.options.image_sort;
+                self.options.image_sort = if c == 'o' { sort.next_key() } else { sort.reversed() };
+                self.image_list.get_mut().set_sort(self.options.image_sort);
+            }
+            (Pane::Volumes, 'o' | 'O') => {
+                let sort = self.options.volume_sort;
+                self.options.volume_sort = if c == 'o' { sort.next_key() } else { sort.reversed() };
+                self.volume_list.get_mut().set_sort(self.options.volume_sort);
+            }
+            _ => return false,
+        }
+        if let Err(err) = self.options.save() {
+            log::warn!("failed to save options: {}", err);
+        }
+        true
+    }
+
+    /// Space marks the selected item for a bulk action or unmarks it, `*` marks
+    /// every listed item and `~` inverts the marks. Returns false when the pane can't be marked
+    fn on_mark_key(&mut self, c: char) -> bool {
+        if !matches!(c, ' ' | '*' | '~') {
+            return false;
+        }
+        match self.selected_pane {
+            Pane::Containers => {
+                let containers = self.container_list.get_mut();
+                match c {
+                    ' ' => containers.toggle_mark(),
+                    '*' => containers.mark_all(),
+                    _ => containers.invert_marks(),
+                }
+                self.follow_selected_logs();
+            }
+            Pane::Images => mark(self.image_list.get_mut().list_mut(), c),
+            Pane::Volumes => mark(self.volume_list.get_mut().list_mut(), c),
+            Pane::Networks => mark(self.network_list.get_mut().list_mut(), c),
+            Pane::Logs => return false,
+        }
+        true
+    }
+
+    fn pane_has_marks(&self) -> bool {
+        match self.selected_pane {
+            Pane::Containers => self.container_list.borrow().has_marks(),
+            Pane::Images => self.image_list.borrow().has_marks(),
+            Pane::Volumes => self.volume_list.borrow().has_marks(),
+            Pane::Networks => self.network_list.borrow().has_marks(),
+            Pane::Logs => false,
+        }
+    }
+
+    fn clear_pane_marks(&mut self) {
+        match self.selected_pane {
+            Pane::Containers => self.container_list.get_mut().clear_marks(),
+            Pane::Images => self.image_list.get_mut().list_mut().clear_marks(),
+            Pane::Volumes => self.volume_list.get_mut().list_mut().clear_marks(),
+            Pane::Networks => self.network_list.get_mut().list_mut().clear_marks(),
+            Pane::Logs => {}
+        }
+    }
+
+    /// Query of the filter of the focused pane, `None` when the pane has no filter
+    fn pane_filter(&self) -> Option<String> {
+        let filter = match self.selected_pane {
+            Pane::Containers => self.container_list.borrow().filter().query().to_string(),
+            Pane::Images => self.image_list.borrow().filter().query().to_string(),
+            Pane::Volumes => self.volume_list.borrow().filter().query().to_string(),
+            Pane::Networks => self.network_list.borrow().filter().query().to_string(),
+            Pane::Logs => return None,
+        };
+        Some(filter)
+    }
+
+    fn set_pane_filter(&mut self, query: &str) {
+        let filter = Filter::new(query);
+        match self.selected_pane {
+            Pane::Containers => {
+                self.container_list.get_mut().set_filter(filter);
+                self.follow_selected_logs();
+            }
+            Pane::Images => self.image_list.get_mut().set_filter(filter),
+            Pane::Volumes => self.volume_list.get_mut().set_filter(filter),
+            Pane::Networks => self.network_list.get_mut().set_filter(filter),
+            Pane::Logs => {}
+        }
+    }
+
+    /// Edits the filter of the focused pane as it is typed, the list follows every key
+    fn handle_filter_key(&mut self, key: Key) {
+        let mut query = self.pane_filter().unwrap_or_default();
+        match key {
+            Key::Char('\n') => self.filtering = false,
+            Key::Esc => {
+                self.filtering = false;
+                self.set_pane_filter("");
+            }
+            Key::Backspace => {
+                query.pop();
+                self.set_pane_filter(&query);
+            }
+            Key::Char(c) => {
+                query.push(c);
+                self.set_pane_filter(&query);
+            }
+            Key::Down | Key::Up | Key::Home | Key::End | Key::PageDown | Key::PageUp => self.on_navigation_key(key),
+            _ => {}
+        }
+    }
+
+    /// Reports how a session that had the terminal ended
+    pub fn session_finished(&mut self, session: &Session, result: Result<Option<i64>, Error>) {
+        match result {
+            Ok(Some(code)) if code != 0 => self.notice = Some(format!("{} exited with code {}", session.description, code)),
+            Ok(_) => {}
+            Err(err) => {
+                self.popup = Some(Popup::Message(MessagePopup::error(&session.description, err.to_string())));
+            }
+        }
+    }
+
+    /// Opens the inspect popup for the selected item of the focused pane
+    fn inspect_selected(&mut self) {
+        let target = match self.selected_pane {
+            Pane::Containers => {
+                // a project or service can't be inspected, it folds instead
+                if self.container_list.get_mut().toggle_collapsed() {
+                    self.follow_selected_logs();
+                    return;
+                }
+                self.container_list.get_mut().selected().map(|container| {
+                    InspectTarget::Container(display_name(container))
+                })
+            }
+            Pane::Images => self.image_list.get_mut().selected().map(|image| {
+                let tag = image.repo_tags.iter().find(|tag| tag.as_str() != "<none>:<none>");
+                InspectTarget::Image(tag.cloned().unwrap_or_else(|| image.id.clone()))
+            }),
+            Pane::Volumes => self.volume_list.get_mut().selected().map(|volume| {
+                InspectTarget::Volume(volume.name.clone())
+            }),
+            Pane::Networks => self.network_list.get_mut().selected().map(|network| {
+                InspectTarget::Network(network.name.clone().unwrap_or_default())
+            }),
+            Pane::Logs => None,
+        };
+        if let Some(target) = target {
+            self.send(IOEvent::Inspect(target));
+        }
+    }
+
+    /// Create, remove and prune volumes, removing one that is in use needs confirming its containers
+    fn on_volume_key(&mut self, c: char) {
+        match c {
+            'c' => {
+                self.popup = Some(Popup::Form(FormPopup::new(
+                    "Create volume",
+                    vec![
+                        Field::new("Name", "empty for a generated one", ""),
+                        Field::new("Driver", "", "local"),
+                        Field::new("Driver options", "key=value, comma separated", ""),
+                        Field::new("Labels", "key=value, comma separated", ""),
+                    ],
+                    Box::new(|form| {
+                        Ok(IOEvent::Volume(VolumeAction::Create {
+                            name: form.value("Name").to_string(),
+                            driver: form.value("Driver").to_string(),
+                            driver_opts: parse_pairs(form.value("Driver options"))?,
+                            labels: parse_pairs(form.value("Labels"))?,
+                        }))
+                    }),
+                )));
+            }
+            'p' => {
+                let unused: Vec<String> = self.volumes
+                    .iter()
+                    .filter(|volume| Volumes::is_unused(volume, self))
+                    .map(|volume| format!("  {}", volume.name))
+                    .collect();
+                if unused.is_empty() {
+                    self.notice = Some("There are no unused volumes to prune".to_string());
+                    return;
+                }
+                let mut lines = vec!["Remove the volumes no container uses?".to_string()];
+                lines.extend(unused);
+                self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                    "Prune volumes",
+                    lines,
+                    vec![],
+                    Box::new(|_| IOEvent::Volume(VolumeAction::Prune)),
+                )));
+            }
+            'd' => {
+                // which containers use them is checked on all of them, the list may only have the running ones
+                let volumes = self.volume_list.get_mut();
+                let names: Vec<String> = if volumes.has_marks() {
+                    volumes.marked().into_iter().map(|volume| volume.name.clone()).collect()
+                } else {
+                    volumes.selected().map(|volume| volume.name.clone()).into_iter().collect()
+                };
+                if !names.is_empty() {
+                    self.send(IOEvent::PreviewVolumeRemove { names });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Create, remove and prune networks and (dis)connect containers to the selected one
+    fn on_network_key(&mut self, c: char) {
+        match c {
+            'c' => {
+                self.popup = Some(Popup::Input(InputPopup::new(
+                    "Create network",
+                    "Name of the new bridge network",
+                    Box::new(|name| Ok(IOEvent::Network(NetworkAction::Create { name: name.to_string() }))),
+                )));
+                return;
+            }
+            'p' => {
+                let unused: Vec<String> = self.networks
+                    .iter()
+                    .filter(|network| network.containers.iter().flatten().next().is_none())
+                    .filter_map(|network| network.name.clone())
+                    .filter(|name| !BUILTIN_NETWORKS.contains(&name.as_str()))
+                    .collect();
+                let mut lines = vec!["Remove every network no container is attached to?".to_string()];
+                lines.extend(unused.iter().map(|name| format!("  {}", name)));
+                self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                    "Prune networks",
+                    lines,
+                    vec![],
+                    Box::new(|_| IOEvent::Network(NetworkAction::Prune)),
+                )));
+                return;
+            }
+            _ => {}
+        }
+
+        if c == 'd' && self.network_list.get_mut().has_marks() {
+            return self.remove_marked_networks();
+        }
+        let (id, name, attached) = match self.network_list.get_mut().selected() {
+            Some(network) => (
+                network.id.clone().unwrap_or_default(),
+                network.name.clone().unwrap_or_default(),
+                Networks::container_names(network),
+            ),
+            None => return,
+        };
+        match c {
+            'd' if BUILTIN_NETWORKS.contains(&name.as_str()) => {
+                self.notice = Some(format!("{} is a predefined network and can't be removed", name));
+            }
+            'd' => {
+                self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                    "Remove network",
+                    vec![format!("Remove network {}?", name)],
+                    vec![],
+                    Box::new(move |_| IOEvent::Network(NetworkAction::Remove { id: id.clone(), name: name.clone() })),
+                )));
+            }
+            '+' => {
+                let containers: Vec<String> = self.containers
+                    .iter()
+                    .map(display_name)
+                    .filter(|container| !attached.contains(container))
+                    .collect();
+                if containers.is_empty() {
+                    self.notice = Some(format!("Every listed container is attached to {}", name));
+                    return;
+                }
+                self.popup = Some(Popup::Choice(ChoicePopup::new(
+                    &format!("Connect to {}", name),
+                    containers,
+                    Box::new(move |container| IOEvent::Network(NetworkAction::Connect {
+                        network: name.clone(),
+                        container: container.to_string(),
+                    })),
+                )));
+            }
+            '-' => {
+                if attached.is_empty() {
+                    self.notice = Some(format!("No container is attached to {}", name));
+                    return;
+                }
+                self.popup = Some(Popup::Choice(ChoicePopup::new(
+                    &format!("Disconnect from {}", name),
+                    attached,
+                    Box::new(move |container| IOEvent::Network(NetworkAction::Disconnect {
+                        network: name.clone(),
+                        container: container.to_string(),
+                    })),
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    /// Asks to remove every marked network, the predefined ones are left alone
+    fn remove_marked_networks(&mut self) {
+        let (builtin, networks): (Vec<_>, Vec<_>) = self.network_list
+            .get_mut()
+            .marked()
+            .into_iter()
+            .map(|network| (network.id.clone().unwrap_or_default(), network.name.clone().unwrap_or_default()))
+            .partition(|(_, name)| BUILTIN_NETWORKS.contains(&name.as_str()));
+        if networks.is_empty() {
+            self.notice = Some("The predefined networks can't be removed".to_string());
+            return;
+        }
+        let count = networks.len();
+        let mut lines = vec![format!("Remove these {} network{}?", count, if count == 1 { "" } else { "s" })];
+        lines.extend(networks.iter().map(|(_, name)| format!("  {}", name)));
+        if !builtin.is_empty() {
+            let names: Vec<&str> = builtin.iter().map(|(_, name)| name.as_str()).collect();
+            lines.push(format!("{} can't be removed, they are predefined.", names.join(", ")));
+        }
+        self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+            "Remove networks",
+            lines,
+            vec![],
+            Box::new(move |_| IOEvent::Bulk {
+                title: "Removing the marked networks".to_string(),
+                actions: networks
+                    .iter()
+                    .map(|(id, name)| BulkAction::Network(NetworkAction::Remove { id: id.clone(), name: name.clone() }))
+                    .collect(),
+            }),
+        )));
+    }
+
+    fn on_image_key(&mut self, c: char) {
+        match c {
+            'a' => self.image_list.get_mut().toggle_untagged(),
+            'P' => {
+                self.popup = Some(Popup::Input(InputPopup::new(
+                    "Pull image",
+                    "Image reference, e.g. nginx:1.19 or ghcr.io/owner/image",
+                    Box::new(|reference| Ok(IOEvent::Pull(reference.to_string()))),
+                )));
+            }
+            'p' => {
+                let choices = vec![PRUNE_DANGLING.to_string(), PRUNE_UNUSED.to_string()];
+                self.popup = Some(Popup::Choice(ChoicePopup::new(
+                    "Prune images",
+                    choices,
+                    Box::new(|choice| IOEvent::PreviewImagePrune { all: choice == PRUNE_UNUSED }),
+                )));
+            }
+            'd' if self.image_list.get_mut().has_marks() => self.remove_marked_images(),
+            'd' | 't' | 'h' => self.on_selected_image_key(c),
+            _ => {}
+        }
+    }
+
+    fn remove_marked_images(&mut self) {
+        let images: Vec<(String, String, Vec<String>)> = self.image_list.get_mut().marked().into_iter().map(image_target).collect();
+        let count = images.len();
+        let mut lines = vec![format!("Remove these {} image{}?", count, if count == 1 { "" } else { "s" })];
+        lines.extend(images.iter().map(|(id, name, _)| format!("  {} ({})", name, short_id(id))));
+        let several_tags = images.iter().any(|(_, _, tags)| tags.len() > 1);
+        self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+            "Remove images",
+            lines,
+            vec![
+                Flag::new('f', "force, also untag them and ignore stopped containers", several_tags),
+                Flag::new('p', "keep untagged parent images", false),
+            ],
+            Box::new(move |popup| IOEvent::Bulk {
+                title: "Removing the marked images".to_string(),
+                actions: images
+                    .iter()
+                    .map(|(id, name, _)| BulkAction::Image(ImageAction::Remove {
+                        id: id.clone(),
+                        name: name.clone(),
+                        force: popup.flag('f'),
+                        no_prune: popup.flag('p'),
+                    }))
+                    .collect(),
+            }),
+        )));
+    }
+
+    fn on_selected_image_key(&mut self, c: char) {
+        let (id, name, tags) = match self.image_list.get_mut().selected() {
+            Some(image) => image_target(image),
+            None => return,
+        };
+        match c {
+            'd' => {
+                let mut lines = vec![format!("Remove image {}?", short_id(&id))];
+                lines.extend(tags.iter().map(|tag| format!("  {}", tag)));
+                self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                    "Remove image",
+                    lines,
+                    vec![
+                        Flag::new('f', "force, also untag it and ignore stopped containers", tags.len() > 1),
+                        Flag::new('p', "keep untagged parent images", false),
+                    ],
+                    Box::new(move |popup| IOEvent::Image(ImageAction::Remove {
+                        id: id.clone(),
+                        name: name.clone(),
+                        force: popup.flag('f'),
+                        no_prune: popup.flag('p'),
+                    })),
+                )));
+            }
+            'h' => self.send(IOEvent::ImageDetails { id, name }),
+            't' => {
+                self.popup = Some(Popup::Input(InputPopup::new(
+                    &format!("Tag {}", name),
+                    "New tag, e.g. registry.example.com/app:v2",
+                    Box::new(move |target| {
+                        // the daemon only tags by name, a digest names content that already exists
+                        if target.contains('@') {
+                            return Err(format!("\"{}\" is a digest, a tag looks like repository:tag", target));
+                        }
+                        Ok(IOEvent::Image(ImageAction::Tag {
+                            id: id.clone(),
+                            name: name.clone(),
+                            target: target.to_string(),
+                        }))
+                    }),
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    /// Switches between running and all containers and remembers the choice
+    fn toggle_all_containers(&mut self) {
+        self.all_containers = !self.all_containers;
+        self.options.all_containers = self.all_containers;
+        if let Err(err) = self.options.save() {
+            log::warn!("failed to save options: {}", err);
+        }
+        self.list_containers();
+    }
+
+    /// Switches between the tree of compose projects and a flat list and remembers the choice
+    fn toggle_group_projects(&mut self) {
+        self.options.group_projects = !self.options.group_projects;
+        if let Err(err) = self.options.save() {
+            log::warn!("failed to save options: {}", err);
+        }
+        self.list_containers();
+    }
+
+    fn list_containers(&mut self) {
+        let (grouped, all) = (self.options.group_projects, self.all_containers);
+        self.container_list.get_mut().set_items(self.containers.clone(), grouped, all);
+        self.follow_selected_logs();
+    }
+
+    /// Lifecycle actions on the selected container, or on every marked container or every container of the selected group
+    fn on_container_key(&mut self, c: char) {
+        match c {
+            'a' => return self.toggle_all_containers(),
+            'g' => return self.toggle_group_projects(),
+            'z' => {
+                if self.container_list.get_mut().toggle_collapsed() {
+                    self.follow_selected_logs();
+                } else {
+                    self.notice = Some("Only projects and services fold, select one to fold it".to_string());
+                }
+                return;
+            }
+            _ if self.container_list.get_mut().has_marks() => return self.on_marked_containers_key(c),
+            _ if self.container_list.get_mut().selected_group().is_some() => return self.on_container_group_key(c),
             _ => {}
         }
+        let (id, name, state) = match self.container_list.get_mut().selected() {
+            Some(container) => (
+                container.id.clone().unwrap_or_default(),
+                display_name(container),
+                container.state.clone().unwrap_or_default(),
+            ),
+            None => return,
+        };
+
+        let action = match c {
+            'u' => ContainerAction::Start,
+            's' => ContainerAction::Stop,
+            'r' => ContainerAction::Restart,
+            'p' if state == "paused" => ContainerAction::Unpause,
+            'p' => ContainerAction::Pause,
+            'k' => {
+                let signals = KILL_SIGNALS.iter().map(|signal| signal.to_string()).collect();
+                self.popup = Some(Popup::Choice(ChoicePopup::new(
+                    &format!("Kill {}", name),
+                    signals,
+                    Box::new(move |signal| IOEvent::Container {
+                        id: id.clone(),
+                        name: name.clone(),
+                        action: ContainerAction::Kill(signal.to_string()),
+                    }),
+                )));
+                return;
+            }
+            'e' if state == "running" => {
+                self.session = Some(Session::exec(&self.host, &id, &name, &self.options.exec_command));
+                return;
+            }
+            'A' if state == "running" => {
+                self.send(IOEvent::Attach { id, name });
+                return;
+            }
+            'e' | 'A' => {
+                self.notice = Some(format!("{} is not running", name));
+                return;
+            }
+            'd' => {
+                self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                    "Remove container",
+                    vec![format!("Remove {} ({})?", name, state)],
+                    vec![
+                        Flag::new('f', "force, kill it first if running", state == "running"),
+                        Flag::new('v', "remove anonymous volumes", false),
+                    ],
+                    Box::new(move |popup| IOEvent::Container {
+                        id: id.clone(),
+                        name: name.clone(),
+                        action: ContainerAction::Remove { force: popup.flag('f'), volumes: popup.flag('v') },
+                    }),
+                )));
+                return;
+            }
+            _ => return,
+        };
+
+        self.notice = Some(format!("{} {}...", action.pending(), name));
+        self.send(IOEvent::Container { id, name, action });
+    }
+
+    fn on_container_group_key(&mut self, c: char) {
+        let list = self.container_list.get_mut();
+        let group = match list.selected_group() {
+            Some(group) => group,
+            None => return,
+        };
+        let label = group.label();
+        let containers = list.group_containers(group).into_iter().map(container_target).collect();
+        self.on_containers_key(c, label, containers);
+    }
+
+    fn on_marked_containers_key(&mut self, c: char) {
+        let containers = self.container_list.get_mut().marked().into_iter().map(container_target).collect();
+        self.on_containers_key(c, "the marked containers".to_string(), containers);
+    }
+
+    /// Runs a lifecycle action concurrently on several containers, given as id, name
+    /// and state. `label` names them, e.g. `project shop`
+    fn on_containers_key(&mut self, c: char, label: String, containers: Vec<(String, String, String)>) {
+        let active = |state: &str| matches!(state, "running" | "paused" | "restarting");
+
+        // the daemon refuses to start a running container or stop a stopped one
+        let (verb, pending, actions) = match c {
+            'u' => ("start", "Starting", container_actions(&containers, |state| {
+                Some(ContainerAction::Start).filter(|_| !active(state))
+            })),
+            's' => ("stop", "Stopping", container_actions(&containers, |state| {
+                Some(ContainerAction::Stop).filter(|_| active(state))
+            })),
+            'r' => ("restart", "Restarting", container_actions(&containers, |_| Some(ContainerAction::Restart))),
+            'p' => ("pause", "Pausing or unpausing", container_actions(&containers, |state| match state {
+                "running" => Some(ContainerAction::Pause),
+                "paused" => Some(ContainerAction::Unpause),
+                _ => None,
+            })),
+            'k' => {
+                if !containers.iter().any(|(_, _, state)| active(state)) {
+                    self.notice = Some(format!("There are no containers to kill in {}", label));
+                    return;
+                }
+                let signals = KILL_SIGNALS.iter().map(|signal| signal.to_string()).collect();
+                self.popup = Some(Popup::Choice(ChoicePopup::new(
+                    &format!("Kill {}", label),
+                    signals,
+                    Box::new(move |signal| IOEvent::Bulk {
+                        title: format!("Killing {}", label),
+                        actions: container_actions(&containers, |state| {
+                            Some(ContainerAction::Kill(signal.to_string())).filter(|_| active(state))
+                        }),
+                    }),
+                )));
+                return;
+            }
+            'd' => {
+                let running = containers.iter().any(|(_, _, state)| state == "running");
+                let count = containers.len();
+                let mut lines = vec![format!("Remove these {} container{}?", count, if count == 1 { "" } else { "s" })];
+                lines.extend(containers.iter().map(|(_, name, state)| format!("  {} ({})", name, state)));
+                self.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                    &format!("Remove {}", label),
+                    lines,
+                    vec![
+                        Flag::new('f', "force, kill them first if running", running),
+                        Flag::new('v', "remove anonymous volumes", false),
+                    ],
+                    Box::new(move |popup| {
+                        let action = ContainerAction::Remove { force: popup.flag('f'), volumes: popup.flag('v') };
+                        IOEvent::Bulk {
+                            title: format!("Removing {}", label),
+                            actions: container_actions(&containers, |_| Some(action.clone())),
+                        }
+                    }),
+                )));
+                return;
+            }
+            _ => return,
+        };
+
+        if actions.is_empty() {
+            self.notice = Some(format!("There are no containers to {} in {}", verb, label));
+            return;
+        }
+        let title = format!("{} {}", pending, label);
+        self.notice = Some(format!("{}...", title));
+        self.send(IOEvent::Bulk { title, actions });
+    }
+
+    fn handle_popup_key(&mut self, key: Key) {
+        let action = match self.popup.as_mut() {
+            Some(popup) => popup.handle_key(key),
+            None => return,
+        };
+        match action {
+            PopupAction::None => {}
+            PopupAction::Close => self.popup = None,
+            PopupAction::Send(event) => {
+                self.popup = None;
+                match &event {
+                    IOEvent::Container { name, action, .. } => {
+                        self.notice = Some(format!("{} {}...", action.pending(), name));
+                    }
+                    IOEvent::Bulk { title, .. } => self.notice = Some(format!("{}...", title)),
+                    IOEvent::Network(action) => self.notice = Some(format!("{}...", action.pending())),
+                    IOEvent::Image(action) => self.notice = Some(format!("{}...", action.pending())),
+                    IOEvent::Volume(action) => self.notice = Some(format!("{}...", action.pending())),
+                    // the progress of a pull replaces the prompt for its reference
+                    IOEvent::Pull(reference) => self.popup = Some(Popup::Pull(PullPopup::new(reference))),
+                    IOEvent::CancelPull => self.notice = Some("Cancelled the pull".to_string()),
+                    _ => {}
+                }
+                self.send(event);
+            }
+        }
     }
 
     pub fn handle_event(&mut self, event: Result<Event<Key>, mpsc::RecvError>) -> Result<bool, Error> {
         let event = event?;
         match event {
+            Event::Input(input) if self.popup.is_some() => self.handle_popup_key(input),
+            Event::Input(input) if self.filtering => self.handle_filter_key(input),
+            // esc clears the marks of the focused pane, then its filter, before it quits
+            Event::Input(Key::Esc) if self.on_containers_tab() && self.pane_has_marks() => self.clear_pane_marks(),
+            Event::Input(Key::Esc) if self.on_containers_tab() && !self.pane_filter().unwrap_or_default().is_empty() => {
+                self.set_pane_filter("");
+            }
             Event::Input(input) => match input {
+                Key::Char('\t') => {
+                    self.selected_pane = self.selected_pane.next();
+                }
                 Key::Char(c) => {
+                    self.notice = None;
                     self.on_key(c);
                 }
-                Key::Down => {
-                   self.tab_state.get_current_tab().handle_event();
+                Key::Down | Key::Up | Key::Home | Key::End | Key::PageDown | Key::PageUp => {
+                    match self.tab_state.get_current_tab() {
+                        Tab::Containers => self.on_navigation_key(input),
+                        Tab::Stats => self.stats_table.get_mut().navigate(input),
+                        Tab::Version => {}
+                    }
                 }
-                Key::Up => {}
-                Key::PageDown => {
+                Key::BackTab => {
+                    self.selected_pane = self.selected_pane.previous();
+                }
+                Key::Right => {
                     self.tab_state.next();
-                    self.selected_tab = self.tab_state.index;
+                    self.on_tab_changed();
                 },
-                Key::PageUp => {
+                Key::Left => {
                     self.tab_state.previous();
-                    self.selected_tab = self.tab_state.index;
+                    self.on_tab_changed();
                 },
                 Key::Backspace | Key::Esc => {
                     self.should_quit = true;
@@ -114,10 +1134,11 @@ impl MainApp {
     }
 
     pub fn get_default_chunks(&self, size: Rect) -> Vec<Rect> {
+        let banner_height = if self.connection.is_connected() { 0 } else { 1 };
         Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
-            .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+            .constraints([Constraint::Length(banner_height), Constraint::Length(3), Constraint::Min(3), Constraint::Length(1)].as_ref())
             .split(size)
     }
 
@@ -126,11 +1147,69 @@ impl MainApp {
         let chunks = self.get_default_chunks(size);
         let block = Block::default().style(Style::default().bg(Color::Black).fg(Color::LightMagenta));
         f.render_widget(block, size);
-        self.draw_tab_bar(f, chunks[0]);
+        self.draw_connection_banner(f, chunks[0]);
+        self.draw_tab_bar(f, chunks[1]);
         let tab = self.tab_state.get_current_tab();
-        if let Err(error) = tab.draw(f, chunks[1], self) {
+        if let Err(error) = tab.draw(f, chunks[2], self) {
             log::error!("There was an error {:?}", error)
         }
+        self.draw_notice(f, chunks[3]);
+        if let Some(popup) = &self.popup {
+            if let Err(error) = popup.draw(f, size, self) {
+                log::error!("There was an error drawing the popup {:?}", error)
+            }
+        }
+    }
+
+    fn draw_notice<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+        if self.filtering {
+            let query = self.pane_filter().unwrap_or_default();
+            f.render_widget(Paragraph::new(Spans::from(vec![
+                Span::styled(format!("/{}_", query), self.theme.text(true, false)),
+                Span::styled(
+                    "  e.g. web status:exited label:env=prod  (enter: keep, esc: clear)",
+                    self.theme.text(false, false),
+                ),
+            ])), r);
+        } else if let Some(notice) = &self.notice {
+            f.render_widget(Paragraph::new(Span::styled(notice.as_str(), self.theme.text(true, false))), r);
+        } else {
+            let hints = if self.on_containers_tab() {
+                format!("{}  {}", self.key_hints(), TAB_HINTS)
+            } else {
+                TAB_HINTS.to_string()
+            };
+            f.render_widget(Paragraph::new(Span::styled(hints, self.theme.text(false, false))), r);
+        }
+    }
+
+    /// The keys of the focused pane, shown where notices go while there is none
+    fn key_hints(&self) -> &'static str {
+        match self.selected_pane {
+            Pane::Containers => {
+                "space: mark  z: fold  enter: inspect  u/s/r/p/k/d: start/stop/restart/pause/kill/remove  \
+                 e: shell  A: attach  a: all  g: group  /: filter  o: sort"
+            }
+            Pane::Images => "space: mark  enter: inspect  P: pull  t: tag  h: history  d: remove  p: prune  a: untagged  /: filter  o: sort",
+            Pane::Volumes => "space: mark  enter: inspect  c: create  d: remove  p: prune  /: filter  o: sort",
+            Pane::Networks => "space: mark  enter: inspect  c: create  +/-: connect/disconnect  d: remove  p: prune  /: filter",
+            Pane::Logs => "f: follow  t: timestamps  [/]: scroll",
+        }
+    }
+
+    fn draw_connection_banner<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
+        let banner = match &self.connection {
+            ConnectionState::Connected => return,
+            ConnectionState::Reconnecting => Span::styled(
+                "Lost connection to the docker daemon, reconnecting...",
+                self.theme.text(false, false),
+            ),
+            ConnectionState::Down(err) => Span::styled(
+                format!("Docker daemon unreachable: {}", err),
+                self.theme.text_danger().add_modifier(Modifier::BOLD),
+            ),
+        };
+        f.render_widget(Paragraph::new(Spans::from(banner)), r);
     }
 
     fn draw_tab_bar<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
@@ -9,7 +9,12 @@ pub mod util;
 pub mod tabs;
 pub mod images;
 pub mod volumes;
+pub mod networks;
 pub mod containers;
+pub mod logs;
+pub mod popups;
+pub mod stats;
+pub mod version;
 
 pub trait DrawableComponent {
     ///
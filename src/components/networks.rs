@@ -0,0 +1,192 @@
+use anyhow::Error;
+use bollard::models::Network;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::Rect;
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, List, ListItem};
+
+use crate::components::MutableDrawableComponent;
+use crate::components::main_app::{MainApp, Pane};
+use crate::components::util::{hidden_marks_note, short_id, Keyed, StatefulList};
+use crate::components::util::filter::{Filter, Searchable};
+
+/// Networks every daemon creates, they can't be removed
+pub const BUILTIN_NETWORKS: [&str; 3] = ["bridge", "host", "none"];
+
+pub struct Networks {
+    items: StatefulList<Network>,
+    all: Vec<Network>,
+    filter: Filter,
+    height: usize,
+}
+
+impl Keyed for Network {
+    fn key(&self) -> &str {
+        self.id.as_deref().or(self.name.as_deref()).unwrap_or_default()
+    }
+}
+
+impl MutableDrawableComponent for Networks {
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        self.height = rect.height.saturating_sub(2) as usize;
+
+        let items = Networks::map_to_list_items(&self.items.items, &self.items.mark_symbols(), &self.filter, app);
+
+        let focused = app.is_focused(Pane::Networks);
+        let title = format!("{}{}", self.filter.title("Networks"), hidden_marks_note(self.items.hidden_marks()));
+        let items = List::new(items)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.block(focused))
+                .title(Span::styled(title, app.theme.title(focused))))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::LightGreen)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+        f.render_stateful_widget(items, rect, &mut self.items.state);
+        Ok(())
+    }
+}
+
+impl Networks {
+    pub fn new() -> Networks {
+        Networks {
+            items: StatefulList::new(),
+            all: vec![],
+            filter: Filter::default(),
+            height: 0,
+        }
+    }
+
+    pub fn set_items(&mut self, networks: Vec<Network>) {
+        self.all = networks;
+        self.items.retain_marks(&self.all);
+        self.apply_filter();
+    }
+
+    fn apply_filter(&mut self) {
+        let filter = &self.filter;
+        let mut networks: Vec<Network> = self.all
+            .iter()
+            .filter(|network| filter.matches(&Networks::searchable(network)))
+            .cloned()
+            .collect();
+        networks.sort_by(|a, b| a.name.cmp(&b.name));
+        self.items.set_items(networks);
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.apply_filter();
+    }
+
+    pub fn selected(&self) -> Option<&Network> {
+        self.items.selected()
+    }
+
+    pub fn list_mut(&mut self) -> &mut StatefulList<Network> {
+        &mut self.items
+    }
+
+    pub fn has_marks(&self) -> bool {
+        self.items.has_marks()
+    }
+
+    /// The marked networks, those the filter hides included
+    pub fn marked(&self) -> Vec<&Network> {
+        self.items.marked(&self.all)
+    }
+
+    pub fn page_height(&self) -> usize {
+        self.height
+    }
+
+    /// Names of the containers attached to the network
+    pub fn container_names(network: &Network) -> Vec<String> {
+        let mut names: Vec<String> = network.containers
+            .iter()
+            .flatten()
+            .filter_map(|(_, container)| container.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn details(network: &Network) -> String {
+        let mut details = vec![
+            network.driver.clone().unwrap_or_default(),
+            network.scope.clone().unwrap_or_default(),
+        ];
+        let configs = network.ipam.as_ref().and_then(|ipam| ipam.config.as_ref());
+        for config in configs.into_iter().flatten() {
+            if let Some(subnet) = config.get("Subnet") {
+                details.push(subnet.clone());
+            }
+            if let Some(gateway) = config.get("Gateway") {
+                details.push(format!("gw {}", gateway));
+            }
+        }
+        details.join(" ")
+    }
+
+    /// What the filter of the pane matches a network against
+    fn searchable(network: &Network) -> Searchable<'_> {
+        let name = network.name.as_deref().unwrap_or_default();
+        let id = network.id.as_deref().unwrap_or_default();
+        Searchable {
+            texts: vec![name, short_id(id)],
+            fields: vec![
+                ("name", name),
+                ("id", id),
+                ("driver", network.driver.as_deref().unwrap_or_default()),
+                ("scope", network.scope.as_deref().unwrap_or_default()),
+            ],
+            labels: network.labels.iter().flatten().map(|(key, value)| (key.as_str(), value.as_str())).collect(),
+        }
+    }
+
+    fn map_to_list_items<'a>(networks: &'a [Network], marks: &[&'static str], filter: &Filter, app: &MainApp) -> Vec<ListItem<'a>> {
+        networks
+            .iter()
+            .zip(marks)
+            .map(|(network, mark)| {
+                let mut name = filter.spans(
+                    network.name.clone().unwrap_or_default(),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                    app.theme.filter_match(),
+                );
+                name.insert(0, Span::raw(*mark));
+                let mut lines = vec![
+                    Spans::from(name),
+                    Spans::from(Span::styled(Networks::details(network), app.theme.text(false, false))),
+                ];
+                let mut containers: Vec<(String, String)> = network.containers
+                    .iter()
+                    .flatten()
+                    .map(|(_, container)| (
+                        container.name.clone().unwrap_or_default(),
+                        container.ipv4_address.clone().unwrap_or_default(),
+                    ))
+                    .collect();
+                containers.sort();
+                for (name, ip) in containers {
+                    lines.push(Spans::from(vec![
+                        Span::raw("  "),
+                        Span::raw(name),
+                        Span::raw(" "),
+                        Span::styled(ip, app.theme.text(false, false)),
+                    ]));
+                }
+                ListItem::new(lines).style(Style::default().fg(Color::Red))
+            })
+            .collect()
+    }
+}
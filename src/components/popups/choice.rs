@@ -0,0 +1,70 @@
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::Rect;
+use tui::widgets::{Block, Borders, Clear, List, ListItem};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+use crate::components::util::StatefulList;
+use crate::docker::IOEvent;
+
+/// Pick one entry out of a short list, the picked entry is turned into an `IOEvent`
+pub struct ChoicePopup {
+    title: String,
+    options: StatefulList<String>,
+    on_select: Box<dyn Fn(&str) -> IOEvent + Send>,
+}
+
+impl ChoicePopup {
+    pub fn new(title: &str, options: Vec<String>, on_select: Box<dyn Fn(&str) -> IOEvent + Send>) -> ChoicePopup {
+        let mut options = StatefulList::with_items(options);
+        options.next();
+        ChoicePopup {
+            title: title.to_string(),
+            options,
+            on_select,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc | Key::Char('q') => PopupAction::Close,
+            Key::Down => {
+                self.options.next();
+                PopupAction::None
+            }
+            Key::Up => {
+                self.options.previous();
+                PopupAction::None
+            }
+            Key::Char('\n') => match self.options.selected() {
+                Some(option) => PopupAction::Send((self.on_select)(option)),
+                None => PopupAction::Close,
+            },
+            _ => PopupAction::None,
+        }
+    }
+}
+
+impl DrawableComponent for ChoicePopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(30, 40, rect);
+        let items: Vec<ListItem> = self.options.items
+            .iter()
+            .map(|option| ListItem::new(option.as_str()))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(self.title.as_str()))
+            .highlight_style(app.theme.text(true, true))
+            .highlight_symbol(">> ");
+
+        f.render_widget(Clear, area);
+        // the options always fit the popup so the scroll offset doesn't need to survive the frame
+        f.render_stateful_widget(list, area, &mut self.options.state.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+use crate::docker::IOEvent;
+
+/// An option of a `ConfirmPopup`, toggled with its key
+pub struct Flag {
+    key: char,
+    label: String,
+    value: bool,
+}
+
+impl Flag {
+    pub fn new(key: char, label: &str, value: bool) -> Flag {
+        Flag {
+            key,
+            label: label.to_string(),
+            value,
+        }
+    }
+}
+
+/// Asks before running a destructive action. Only `y` confirms, Enter cancels
+/// like the other keys so a stray one doesn't remove anything.
+pub struct ConfirmPopup {
+    title: String,
+    lines: Vec<String>,
+    /// First of the lines shown, long lists such as the images a prune removes scroll
+    scroll: u16,
+    flags: Vec<Flag>,
+    on_confirm: Box<dyn Fn(&ConfirmPopup) -> IOEvent + Send>,
+}
+
+impl ConfirmPopup {
+    pub fn new(title: &str, lines: Vec<String>, flags: Vec<Flag>, on_confirm: Box<dyn Fn(&ConfirmPopup) -> IOEvent + Send>) -> ConfirmPopup {
+        ConfirmPopup {
+            title: title.to_string(),
+            lines,
+            scroll: 0,
+            flags,
+            on_confirm,
+        }
+    }
+
+    /// Current value of the flag bound to `key`
+    pub fn flag(&self, key: char) -> bool {
+        self.flags.iter().any(|flag| flag.key == key && flag.value)
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc | Key::Char('n') | Key::Char('q') | Key::Char('\n') => PopupAction::Close,
+            Key::Char('y') => PopupAction::Send((self.on_confirm)(self)),
+            Key::Down | Key::PageDown => {
+                let step = if key == Key::Down { 1 } else { 10 };
+                let last = self.lines.len().saturating_sub(1) as u16;
+                self.scroll = (self.scroll + step).min(last);
+                PopupAction::None
+            }
+            Key::Up | Key::PageUp => {
+                self.scroll = self.scroll.saturating_sub(if key == Key::Up { 1 } else { 10 });
+                PopupAction::None
+            }
+            Key::Char(c) => {
+                if let Some(flag) = self.flags.iter_mut().find(|flag| flag.key == c) {
+                    flag.value = !flag.value;
+                }
+                PopupAction::None
+            }
+            _ => PopupAction::None,
+        }
+    }
+}
+
+impl DrawableComponent for ConfirmPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(50, 40, rect);
+        let lines: Vec<Spans> = self.lines
+            .iter()
+            .map(|line| Spans::from(line.as_str()))
+            .collect();
+
+        // the flags and the keys stay put below the lines
+        let mut footer: Vec<Spans> = vec![];
+        if !self.flags.is_empty() {
+            footer.push(Spans::default());
+        }
+        for flag in &self.flags {
+            footer.push(Spans::from(vec![
+                Span::styled(format!("[{}] ", if flag.value { 'x' } else { ' ' }), app.theme.option(flag.value)),
+                Span::raw(format!("{} ({})", flag.label, flag.key)),
+            ]));
+        }
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        let overflows = lines.len() + footer.len() + 2 > inner.height as usize;
+        let keys = if overflows {
+            "y: confirm  n/Esc/Enter: cancel  ↑/↓: scroll"
+        } else {
+            "y: confirm  n/Esc/Enter: cancel"
+        };
+        footer.push(Spans::default());
+        footer.push(Spans::from(Span::styled(keys, app.theme.text(false, false))));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(footer.len() as u16)].as_ref())
+            .split(inner);
+
+        f.render_widget(Clear, area);
+        f.render_widget(Block::default().borders(Borders::ALL).title(self.title.as_str()), area);
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).scroll((self.scroll, 0)), chunks[0]);
+        f.render_widget(Paragraph::new(footer).wrap(Wrap { trim: false }), chunks[1]);
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::Rect;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+use crate::docker::IOEvent;
+
+/// Turns the submitted form into an `IOEvent`, or says what is wrong with it
+pub type OnSubmit = Box<dyn Fn(&FormPopup) -> Result<IOEvent, String> + Send>;
+
+pub struct Field {
+    label: &'static str,
+    hint: &'static str,
+    value: String,
+}
+
+impl Field {
+    pub fn new(label: &'static str, hint: &'static str, value: &str) -> Field {
+        Field {
+            label,
+            hint,
+            value: value.to_string(),
+        }
+    }
+}
+
+/// A few lines of text submitted together, the submit callback may reject them
+/// with a message shown under the fields
+pub struct FormPopup {
+    title: String,
+    fields: Vec<Field>,
+    selected: usize,
+    error: Option<String>,
+    on_submit: OnSubmit,
+}
+
+impl FormPopup {
+    pub fn new(title: &str, fields: Vec<Field>, on_submit: OnSubmit) -> FormPopup {
+        FormPopup {
+            title: title.to_string(),
+            fields,
+            selected: 0,
+            error: None,
+            on_submit,
+        }
+    }
+
+    /// Trimmed value of the field with `label`
+    pub fn value(&self, label: &str) -> &str {
+        self.fields
+            .iter()
+            .find(|field| field.label == label)
+            .map_or("", |field| field.value.trim())
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc => return PopupAction::Close,
+            Key::Char('\n') => match (self.on_submit)(self) {
+                Ok(event) => return PopupAction::Send(event),
+                Err(err) => self.error = Some(err),
+            },
+            Key::Char('\t') | Key::Down => self.selected = (self.selected + 1) % self.fields.len(),
+            Key::BackTab | Key::Up => self.selected = (self.selected + self.fields.len() - 1) % self.fields.len(),
+            Key::Backspace => {
+                self.fields[self.selected].value.pop();
+            }
+            Key::Char(c) => self.fields[self.selected].value.push(c),
+            _ => {}
+        }
+        PopupAction::None
+    }
+}
+
+impl DrawableComponent for FormPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(60, 50, rect);
+        let mut text = vec![];
+        for (index, field) in self.fields.iter().enumerate() {
+            let selected = index == self.selected;
+            text.push(Spans::from(vec![
+                Span::styled(field.label, app.theme.title(selected)),
+                Span::styled(format!("  {}", field.hint), app.theme.text(false, false)),
+            ]));
+            let mut value = vec![Span::raw("> "), Span::raw(field.value.as_str())];
+            if selected {
+                value.push(Span::styled("_", app.theme.text(false, false)));
+            }
+            text.push(Spans::from(value));
+            text.push(Spans::from(""));
+        }
+        match &self.error {
+            Some(error) => text.push(Spans::from(Span::styled(error.as_str(), app.theme.text_danger()))),
+            None => text.push(Spans::from(Span::styled(
+                "tab: next field  enter: submit  esc: cancel",
+                app.theme.text(false, false),
+            ))),
+        }
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(self.title.as_str())),
+            area,
+        );
+        Ok(())
+    }
+}
+
+/// Parses `key=value` pairs separated by commas, e.g. `type=nfs,o=addr=10.0.0.1`
+pub fn parse_pairs(text: &str) -> Result<HashMap<String, String>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.find('=') {
+            Some(index) if index > 0 => Ok((pair[..index].to_string(), pair[index + 1..].to_string())),
+            _ => Err(format!("\"{}\" isn't a key=value pair", pair)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pairs_splits_at_the_first_equals_sign() {
+        let pairs = parse_pairs("type=nfs, o=addr=10.0.0.1,device=:/export").unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs["type"], "nfs");
+        assert_eq!(pairs["o"], "addr=10.0.0.1");
+        assert_eq!(pairs["device"], ":/export");
+    }
+
+    #[test]
+    fn parse_pairs_skips_empty_pairs() {
+        assert!(parse_pairs("").unwrap().is_empty());
+        assert_eq!(parse_pairs("a=1,,").unwrap()["a"], "1");
+        assert_eq!(parse_pairs("empty=").unwrap()["empty"], "");
+    }
+
+    #[test]
+    fn parse_pairs_rejects_pairs_without_a_key() {
+        assert_eq!(parse_pairs("a=1,nfs"), Err("\"nfs\" isn't a key=value pair".to_string()));
+        assert!(parse_pairs("=value").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use std::cell::Cell;
+
+use anyhow::Error;
+use bollard::models::{ContainerConfig, HistoryResponseItem};
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState, Wrap};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+use crate::components::util::{format_age, format_bytes, short_id};
+
+/// Prefix the builder puts in front of instructions that don't run anything
+const NOP_PREFIX: &str = "/bin/sh -c #(nop) ";
+
+/// Why an image is as large as it is: its layers, largest first, and the
+/// config its containers start with
+pub struct ImageDetailsPopup {
+    name: String,
+    history: Vec<HistoryResponseItem>,
+    config: Vec<(&'static str, String)>,
+    selected: usize,
+    offset: Cell<usize>,
+    config_scroll: u16,
+}
+
+impl ImageDetailsPopup {
+    pub fn new(name: &str, history: Vec<HistoryResponseItem>, config: Option<ContainerConfig>) -> ImageDetailsPopup {
+        ImageDetailsPopup {
+            name: name.to_string(),
+            history,
+            config: config.map(|config| ImageDetailsPopup::config_entries(&config)).unwrap_or_default(),
+            selected: 0,
+            offset: Cell::new(0),
+            config_scroll: 0,
+        }
+    }
+
+    fn config_entries(config: &ContainerConfig) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            ("Entrypoint", config.entrypoint.as_ref().map(|args| args.join(" ")).unwrap_or_default()),
+            ("Cmd", config.cmd.as_ref().map(|args| args.join(" ")).unwrap_or_default()),
+            ("Working dir", config.working_dir.clone().unwrap_or_default()),
+            ("User", config.user.clone().unwrap_or_default()),
+        ];
+        let mut ports: Vec<&String> = config.exposed_ports.iter().flat_map(|ports| ports.keys()).collect();
+        ports.sort();
+        entries.push(("Exposed ports", ports.into_iter().cloned().collect::<Vec<String>>().join(", ")));
+        for env in config.env.iter().flatten() {
+            entries.push(("Env", env.clone()));
+        }
+        let mut labels: Vec<(&String, &String)> = config.labels.iter().flatten().collect();
+        labels.sort();
+        for (key, value) in labels {
+            entries.push(("Label", format!("{}={}", key, value)));
+        }
+        entries
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        let last = self.history.len().saturating_sub(1);
+        match key {
+            Key::Esc | Key::Char('q') | Key::Char('\n') => return PopupAction::Close,
+            Key::Down => self.selected = (self.selected + 1).min(last),
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::PageDown => self.selected = (self.selected + 10).min(last),
+            Key::PageUp => self.selected = self.selected.saturating_sub(10),
+            Key::Home => self.selected = 0,
+            Key::End => self.selected = last,
+            Key::Char('[') => self.config_scroll = self.config_scroll.saturating_sub(1),
+            Key::Char(']') => self.config_scroll = self.config_scroll.saturating_add(1),
+            _ => {}
+        }
+        PopupAction::None
+    }
+}
+
+impl DrawableComponent for ImageDetailsPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(90, 85, rect);
+        f.render_widget(Clear, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+            .split(area);
+
+        let config: Vec<Spans> = self.config
+            .iter()
+            .map(|(key, value)| Spans::from(vec![
+                Span::styled(format!("{:<14}", key), app.theme.commit_hash(false)),
+                Span::raw(value.as_str()),
+            ]))
+            .collect();
+        f.render_widget(
+            Paragraph::new(config)
+                .block(Block::default().borders(Borders::ALL).title(format!("{} config ([/]: scroll)", self.name)))
+                .wrap(Wrap { trim: false })
+                .scroll((self.config_scroll, 0)),
+            chunks[0],
+        );
+
+        // borders, header and the gap below it
+        let height = chunks[1].height.saturating_sub(4) as usize;
+        let mut offset = self.offset.get();
+        if self.selected < offset {
+            offset = self.selected;
+        } else if height > 0 && self.selected >= offset + height {
+            offset = self.selected + 1 - height;
+        }
+        self.offset.set(offset);
+
+        let total: i64 = self.history.iter().map(|layer| layer.size.max(0)).sum();
+        let rows = self.history.iter().skip(offset).take(height).map(|layer| {
+            let id = if layer.id == "<missing>" { "" } else { short_id(&layer.id) };
+            Row::Data(vec![
+                format_bytes(layer.size.max(0) as u64),
+                format_age(layer.created),
+                id.to_string(),
+                layer.created_by.trim_start_matches(NOP_PREFIX).to_string(),
+            ].into_iter())
+        });
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Length(16),
+            Constraint::Length(12),
+            Constraint::Percentage(100),
+        ];
+        let title = format!("History, {} layers, {} in total", self.history.len(), format_bytes(total as u64));
+        let table = Table::new(["Size", "Created", "ID", "Created by"].iter(), rows)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .header_style(app.theme.title(true))
+            .highlight_style(app.theme.text(true, true))
+            .widths(&widths);
+        let mut state = TableState::default();
+        state.select(Some(self.selected - offset).filter(|_| !self.history.is_empty()));
+        f.render_stateful_widget(table, chunks[1], &mut state);
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::Rect;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+use crate::docker::IOEvent;
+
+/// Turns the submitted text into an `IOEvent` or says why it can't be submitted
+pub type OnInput = Box<dyn Fn(&str) -> Result<IOEvent, String> + Send>;
+
+/// Asks for a line of text, the submit callback may reject it with a message shown under it
+pub struct InputPopup {
+    title: String,
+    label: String,
+    value: String,
+    error: Option<String>,
+    on_submit: OnInput,
+}
+
+impl InputPopup {
+    pub fn new(title: &str, label: &str, on_submit: OnInput) -> InputPopup {
+        InputPopup {
+            title: title.to_string(),
+            label: label.to_string(),
+            value: String::new(),
+            error: None,
+            on_submit,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc => return PopupAction::Close,
+            Key::Char('\n') => {
+                let value = self.value.trim();
+                if !value.is_empty() {
+                    match (self.on_submit)(value) {
+                        Ok(event) => return PopupAction::Send(event),
+                        Err(err) => self.error = Some(err),
+                    }
+                }
+            }
+            Key::Backspace => {
+                self.value.pop();
+            }
+            Key::Char(c) => self.value.push(c),
+            _ => {}
+        }
+        PopupAction::None
+    }
+}
+
+impl DrawableComponent for InputPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(50, 20, rect);
+        let mut text = vec![
+            Spans::from(Span::styled(self.label.as_str(), app.theme.text(false, false))),
+            Spans::from(""),
+            Spans::from(vec![
+                Span::raw("> "),
+                Span::styled(self.value.as_str(), app.theme.text(true, false)),
+                Span::styled("_", app.theme.text(false, false)),
+            ]),
+        ];
+        if let Some(error) = &self.error {
+            text.push(Spans::from(""));
+            text.push(Spans::from(Span::styled(error.as_str(), app.theme.text_danger())));
+        }
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(self.title.as_str())),
+            area,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,378 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+
+use anyhow::Error;
+use serde_json::Value;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+
+use crate::clipboard::copy_string;
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+
+const HELP: &str = "enter: fold  /: search  n: next  c: copy path  y: copy value  w: write json  esc: close";
+
+/// One visible line of the tree
+struct Node {
+    /// jq style path, e.g. `.Config.Env[0]`
+    path: String,
+    /// JSON pointer of the value, e.g. `/Config/Env/0`
+    pointer: String,
+    key: String,
+    depth: usize,
+    /// Number of children when the node is an object or an array
+    children: Option<usize>,
+    text: String,
+}
+
+/// Inspect payload of a docker object as a foldable key/value tree
+pub struct InspectPopup {
+    title: String,
+    value: Value,
+    collapsed: HashSet<String>,
+    nodes: Vec<Node>,
+    selected: usize,
+    offset: Cell<usize>,
+    search: Option<String>,
+    query: String,
+    status: Option<String>,
+}
+
+impl InspectPopup {
+    pub fn new(title: &str, value: Value) -> InspectPopup {
+        let mut popup = InspectPopup {
+            title: title.to_string(),
+            value,
+            collapsed: HashSet::new(),
+            nodes: vec![],
+            selected: 0,
+            offset: Cell::new(0),
+            search: None,
+            query: String::new(),
+            status: None,
+        };
+        popup.rebuild();
+        popup
+    }
+
+    fn rebuild(&mut self) {
+        let mut nodes = vec![];
+        walk(&self.value, "", "", "", 0, Some(&self.collapsed), &mut nodes);
+        self.nodes = nodes;
+        self.selected = self.selected.min(self.nodes.len().saturating_sub(1));
+    }
+
+    fn selected_node(&self) -> Option<&Node> {
+        self.nodes.get(self.selected)
+    }
+
+    fn select_path(&mut self, path: &str) {
+        if let Some(index) = self.nodes.iter().position(|node| node.path == path) {
+            self.selected = index;
+        }
+    }
+
+    fn set_folded(&mut self, folded: bool) {
+        let path = match self.selected_node() {
+            Some(node) if node.children.is_some() => node.path.clone(),
+            _ => return,
+        };
+        if folded {
+            self.collapsed.insert(path);
+        } else {
+            self.collapsed.remove(&path);
+        }
+        self.rebuild();
+    }
+
+    fn toggle_fold(&mut self) {
+        let folded = match self.selected_node() {
+            Some(node) => self.collapsed.contains(&node.path),
+            None => return,
+        };
+        self.set_folded(!folded);
+    }
+
+    /// Jumps to the next node after the selection whose key or value contains the
+    /// query, unfolding whatever hides it
+    fn find_next(&mut self) {
+        if self.query.is_empty() {
+            return;
+        }
+        let mut all = vec![];
+        walk(&self.value, "", "", "", 0, None, &mut all);
+        let query = self.query.to_lowercase();
+        let current = self.selected_node().map(|node| node.path.clone()).unwrap_or_default();
+        let start = all.iter().position(|node| node.path == current).map_or(0, |i| i + 1);
+        let found = all[start..]
+            .iter()
+            .chain(all[..start].iter())
+            .find(|node| node.key.to_lowercase().contains(&query) || node.text.to_lowercase().contains(&query))
+            .map(|node| node.path.clone());
+
+        match found {
+            Some(path) => {
+                self.collapsed.retain(|folded| !is_ancestor(folded, &path));
+                self.rebuild();
+                self.select_path(&path);
+                self.status = None;
+            }
+            None => self.status = Some(format!("No match for \"{}\"", self.query)),
+        }
+    }
+
+    fn copy(&mut self, value: bool) {
+        let text = match self.selected_node() {
+            Some(node) if value => match self.value.pointer(&node.pointer) {
+                Some(Value::String(text)) => text.clone(),
+                Some(other) => serde_json::to_string_pretty(other).unwrap_or_default(),
+                None => return,
+            },
+            Some(node) => node.path.clone(),
+            None => return,
+        };
+        self.status = Some(match copy_string(&text) {
+            Ok(()) => format!("Copied {}", if value { "value" } else { "path" }),
+            Err(err) => format!("Copy failed: {}", err),
+        });
+    }
+
+    /// Writes the whole payload to a json file in the working directory
+    fn write_file(&mut self) {
+        let name: String = self.title
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let path = format!("dockerui-inspect-{}.json", name);
+        let result = serde_json::to_string_pretty(&self.value)
+            .map_err(Error::from)
+            .and_then(|data| {
+                File::create(&path)?.write_all(data.as_bytes())?;
+                Ok(())
+            });
+        self.status = Some(match result {
+            Ok(()) => format!("Wrote {}", path),
+            Err(err) => format!("Writing {} failed: {}", path, err),
+        });
+    }
+
+    fn handle_search_key(&mut self, key: Key) {
+        match key {
+            Key::Esc => self.search = None,
+            Key::Char('\n') => {
+                if let Some(search) = self.search.take() {
+                    self.query = search;
+                    self.find_next();
+                }
+            }
+            Key::Backspace => {
+                if let Some(search) = self.search.as_mut() {
+                    search.pop();
+                }
+            }
+            Key::Char(c) => {
+                if let Some(search) = self.search.as_mut() {
+                    search.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        if self.search.is_some() {
+            self.handle_search_key(key);
+            return PopupAction::None;
+        }
+        let last = self.nodes.len().saturating_sub(1);
+        match key {
+            Key::Esc | Key::Char('q') => return PopupAction::Close,
+            Key::Down => self.selected = (self.selected + 1).min(last),
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::PageDown => self.selected = (self.selected + 10).min(last),
+            Key::PageUp => self.selected = self.selected.saturating_sub(10),
+            Key::Home => self.selected = 0,
+            Key::End => self.selected = last,
+            Key::Char('\n') | Key::Char(' ') => self.toggle_fold(),
+            Key::Right => self.set_folded(false),
+            Key::Left => self.set_folded(true),
+            Key::Char('/') => self.search = Some(String::new()),
+            Key::Char('n') => self.find_next(),
+            Key::Char('c') => self.copy(false),
+            Key::Char('y') => self.copy(true),
+            Key::Char('w') => self.write_file(),
+            _ => {}
+        }
+        PopupAction::None
+    }
+}
+
+impl DrawableComponent for InspectPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(80, 80, rect);
+        f.render_widget(Clear, area);
+
+        let block = Block::default().borders(Borders::ALL).title(self.title.as_str());
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+            .split(inner);
+
+        // keep the selection on screen, the offset survives between frames so
+        // scrolling back up doesn't jump
+        let height = chunks[0].height as usize;
+        let mut offset = self.offset.get();
+        if self.selected < offset {
+            offset = self.selected;
+        } else if height > 0 && self.selected >= offset + height {
+            offset = self.selected + 1 - height;
+        }
+        self.offset.set(offset);
+
+        let items: Vec<ListItem> = self.nodes
+            .iter()
+            .skip(offset)
+            .take(height)
+            .map(|node| {
+                let marker = match node.children {
+                    Some(_) if self.collapsed.contains(&node.path) => "▸ ",
+                    Some(_) => "▾ ",
+                    None => "  ",
+                };
+                let value = match node.children {
+                    Some(_) if self.collapsed.contains(&node.path) => Span::styled(
+                        format!("{} ({})", node.text, node.children.unwrap_or_default()),
+                        app.theme.text(false, false),
+                    ),
+                    _ => Span::raw(node.text.as_str()),
+                };
+                ListItem::new(Spans::from(vec![
+                    Span::raw(format!("{}{}", "  ".repeat(node.depth), marker)),
+                    Span::styled(format!("{}: ", node.key), app.theme.commit_hash(false)),
+                    value,
+                ]))
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(self.selected - offset).filter(|_| !self.nodes.is_empty()));
+        f.render_stateful_widget(
+            List::new(items).highlight_style(app.theme.text(true, true)),
+            chunks[0],
+            &mut state,
+        );
+
+        let footer = match (&self.search, &self.status) {
+            (Some(search), _) => Span::raw(format!("/{}", search)),
+            (None, Some(status)) => Span::styled(status.as_str(), app.theme.text(true, false)),
+            (None, None) => Span::styled(HELP, app.theme.text(false, false)),
+        };
+        f.render_widget(Paragraph::new(Spans::from(footer)), chunks[1]);
+        Ok(())
+    }
+}
+
+/// Flattens `value` into nodes, skipping the children of collapsed paths when given
+fn walk(
+    value: &Value,
+    path: &str,
+    pointer: &str,
+    key: &str,
+    depth: usize,
+    collapsed: Option<&HashSet<String>>,
+    nodes: &mut Vec<Node>,
+) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map.iter().map(|(key, child)| (key.clone(), child)).collect(),
+        Value::Array(items) => items.iter().enumerate().map(|(i, child)| (i.to_string(), child)).collect(),
+        _ => vec![],
+    };
+
+    let is_root = path.is_empty();
+    if !is_root {
+        let (count, text) = match value {
+            Value::Object(map) => (Some(map.len()), "{…}".to_string()),
+            Value::Array(items) => (Some(items.len()), "[…]".to_string()),
+            other => (None, other.to_string()),
+        };
+        nodes.push(Node {
+            path: path.to_string(),
+            pointer: pointer.to_string(),
+            key: key.to_string(),
+            depth,
+            children: count,
+            text,
+        });
+        if let Some(collapsed) = collapsed {
+            if collapsed.contains(path) {
+                return;
+            }
+        }
+    }
+
+    let depth = if is_root { 0 } else { depth + 1 };
+    for (key, child) in children {
+        let child_path = match value {
+            Value::Array(_) => format!("{}[{}]", path, key),
+            _ => object_path(path, &key),
+        };
+        let child_pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+        walk(child, &child_path, &child_pointer, &key, depth, collapsed, nodes);
+    }
+}
+
+/// Keys that aren't plain identifiers are quoted like jq does, e.g. `.Labels["com.docker.compose.project"]`
+fn object_path(parent: &str, key: &str) -> String {
+    let plain = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        format!("{}.{}", parent, key)
+    } else {
+        format!("{}[{}]", parent, Value::String(key.to_string()))
+    }
+}
+
+fn is_ancestor(ancestor: &str, path: &str) -> bool {
+    path.len() > ancestor.len()
+        && path.starts_with(ancestor)
+        && path[ancestor.len()..].starts_with(&['.', '['][..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_path_quotes_keys_that_are_not_identifiers() {
+        assert_eq!(object_path("", "Config"), ".Config");
+        assert_eq!(object_path(".Config", "Image_1"), ".Config.Image_1");
+        assert_eq!(
+            object_path(".Config.Labels", "com.docker.compose.project"),
+            ".Config.Labels[\"com.docker.compose.project\"]"
+        );
+        assert_eq!(object_path(".Ports", "80/tcp"), ".Ports[\"80/tcp\"]");
+        assert_eq!(object_path("", "1st"), "[\"1st\"]");
+        assert_eq!(object_path("", ""), "[\"\"]");
+    }
+
+    #[test]
+    fn is_ancestor_stops_at_path_boundaries() {
+        assert!(is_ancestor("", ".Config"));
+        assert!(is_ancestor(".Config", ".Config.Env"));
+        assert!(is_ancestor(".Config.Env", ".Config.Env[0]"));
+        assert!(is_ancestor(".Config.Labels", ".Config.Labels[\"a.b\"]"));
+        assert!(!is_ancestor(".Config", ".Config"));
+        assert!(!is_ancestor(".Config", ".ConfigFile"));
+        assert!(!is_ancestor(".Config.Env", ".Config"));
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::Rect;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+
+/// Informational or error text, dismissed with Enter or Esc
+pub struct MessagePopup {
+    title: String,
+    lines: Vec<String>,
+    error: bool,
+}
+
+impl MessagePopup {
+    pub fn info(title: &str, lines: Vec<String>) -> MessagePopup {
+        MessagePopup {
+            title: title.to_string(),
+            lines,
+            error: false,
+        }
+    }
+
+    pub fn error(title: &str, message: String) -> MessagePopup {
+        MessagePopup {
+            title: title.to_string(),
+            lines: message.lines().map(String::from).collect(),
+            error: true,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc | Key::Char('\n') | Key::Char('q') => PopupAction::Close,
+            _ => PopupAction::None,
+        }
+    }
+}
+
+impl DrawableComponent for MessagePopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(60, 40, rect);
+        let style = if self.error {
+            app.theme.text_danger()
+        } else {
+            app.theme.text(true, false)
+        };
+        let text: Vec<Spans> = self.lines
+            .iter()
+            .map(|line| Spans::from(Span::styled(line.as_str(), style)))
+            .collect();
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text)
+                .block(Block::default().borders(Borders::ALL).title(self.title.as_str()))
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::docker::IOEvent;
+pub use choice::ChoicePopup;
+pub use confirm::{ConfirmPopup, Flag};
+pub use input::InputPopup;
+pub use form::{parse_pairs, Field, FormPopup};
+pub use image_details::ImageDetailsPopup;
+pub use inspect::InspectPopup;
+pub use pull::{PullPopup, PullState};
+pub use message::MessagePopup;
+pub use summary::SummaryPopup;
+
+mod choice;
+mod confirm;
+mod form;
+mod image_details;
+mod input;
+mod inspect;
+mod message;
+mod pull;
+mod summary;
+
+/// Modal dialog drawn on top of the current tab, it receives every key while open
+pub enum Popup {
+    Message(MessagePopup),
+    Choice(ChoicePopup),
+    Confirm(ConfirmPopup),
+    Inspect(InspectPopup),
+    Input(InputPopup),
+    Pull(PullPopup),
+    ImageDetails(ImageDetailsPopup),
+    Form(FormPopup),
+    Summary(SummaryPopup),
+}
+
+/// What the app should do after a popup handled a key
+pub enum PopupAction {
+    None,
+    Close,
+    Send(IOEvent),
+}
+
+impl Popup {
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match self {
+            Popup::Message(popup) => popup.handle_key(key),
+            Popup::Choice(popup) => popup.handle_key(key),
+            Popup::Confirm(popup) => popup.handle_key(key),
+            Popup::Inspect(popup) => popup.handle_key(key),
+            Popup::Input(popup) => popup.handle_key(key),
+            Popup::Pull(popup) => popup.handle_key(key),
+            Popup::ImageDetails(popup) => popup.handle_key(key),
+            Popup::Form(popup) => popup.handle_key(key),
+            Popup::Summary(popup) => popup.handle_key(key),
+        }
+    }
+}
+
+impl DrawableComponent for Popup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        match self {
+            Popup::Message(popup) => popup.draw(f, rect, app),
+            Popup::Choice(popup) => popup.draw(f, rect, app),
+            Popup::Confirm(popup) => popup.draw(f, rect, app),
+            Popup::Inspect(popup) => popup.draw(f, rect, app),
+            Popup::Input(popup) => popup.draw(f, rect, app),
+            Popup::Pull(popup) => popup.draw(f, rect, app),
+            Popup::ImageDetails(popup) => popup.draw(f, rect, app),
+            Popup::Form(popup) => popup.draw(f, rect, app),
+            Popup::Summary(popup) => popup.draw(f, rect, app),
+        }
+    }
+}
+
+/// Area of `percent_x` by `percent_y` in the middle of `r`
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+                .as_ref(),
+        )
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+                .as_ref(),
+        )
+        .split(popup_layout[1])[1]
+}
@@ -0,0 +1,177 @@
+use anyhow::Error;
+use bollard::models::BuildInfo;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Gauge, Paragraph};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+use crate::components::util::format_bytes;
+use crate::docker::IOEvent;
+
+/// Width of the progress bar of a layer
+const BAR_WIDTH: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PullState {
+    Running,
+    Done(String),
+    Failed(String),
+}
+
+struct Layer {
+    id: String,
+    status: String,
+    current: u64,
+    total: u64,
+}
+
+impl Layer {
+    /// Downloading counts for the first half of a layer, extracting for the second
+    fn progress(&self) -> f64 {
+        let fraction = if self.total > 0 {
+            (self.current as f64 / self.total as f64).min(1.0)
+        } else {
+            0.0
+        };
+        match self.status.as_str() {
+            "Downloading" => fraction / 2.0,
+            "Verifying Checksum" | "Download complete" => 0.5,
+            "Extracting" => 0.5 + fraction / 2.0,
+            "Pull complete" | "Already exists" => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Progress of an image pull, one line per layer as the daemon reports them
+pub struct PullPopup {
+    reference: String,
+    layers: Vec<Layer>,
+    /// Latest message that isn't about a layer, e.g. `Digest: sha256:…`
+    message: String,
+    state: PullState,
+}
+
+impl PullPopup {
+    pub fn new(reference: &str) -> PullPopup {
+        PullPopup {
+            reference: reference.to_string(),
+            layers: vec![],
+            message: format!("Pulling {}", reference),
+            state: PullState::Running,
+        }
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    pub fn update(&mut self, info: &BuildInfo) {
+        if let Some(error) = &info.error {
+            self.state = PullState::Failed(error.clone());
+            return;
+        }
+        let status = info.status.clone().unwrap_or_default();
+        let detail = info.progress_detail.as_ref();
+        match &info.id {
+            // layer ids are short hex digests, anything else is the tag being pulled
+            Some(id) if id.len() == 12 && id.chars().all(|c| c.is_ascii_hexdigit()) => {
+                let index = match self.layers.iter().position(|layer| &layer.id == id) {
+                    Some(index) => index,
+                    None => {
+                        self.layers.push(Layer { id: id.clone(), status: String::new(), current: 0, total: 0 });
+                        self.layers.len() - 1
+                    }
+                };
+                let layer = &mut self.layers[index];
+                layer.status = status;
+                layer.current = detail.and_then(|detail| detail.current).unwrap_or(0).max(0) as u64;
+                layer.total = detail.and_then(|detail| detail.total).unwrap_or(0).max(0) as u64;
+            }
+            _ => self.message = status,
+        }
+    }
+
+    pub fn finish(&mut self, state: PullState) {
+        // an error reported in the stream takes precedence over the stream ending
+        if self.state == PullState::Running {
+            self.state = state;
+        }
+    }
+
+    fn progress(&self) -> f64 {
+        match self.state {
+            PullState::Done(_) => 1.0,
+            _ if self.layers.is_empty() => 0.0,
+            _ => self.layers.iter().map(Layer::progress).sum::<f64>() / self.layers.len() as f64,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc | Key::Char('q') if self.state == PullState::Running => PopupAction::Send(IOEvent::CancelPull),
+            Key::Esc | Key::Char('q') | Key::Char('\n') if self.state != PullState::Running => PopupAction::Close,
+            _ => PopupAction::None,
+        }
+    }
+}
+
+impl DrawableComponent for PullPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(70, 60, rect);
+        f.render_widget(Clear, area);
+
+        let block = Block::default().borders(Borders::ALL).title(format!("Pull {}", self.reference));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)].as_ref())
+            .split(inner);
+
+        let progress = self.progress();
+        f.render_widget(
+            Gauge::default()
+                .gauge_style(app.theme.option(true))
+                .ratio(progress)
+                .label(format!("{:.0}%", progress * 100.0)),
+            chunks[0],
+        );
+
+        let layers: Vec<Spans> = self.layers
+            .iter()
+            .map(|layer| {
+                let filled = (layer.progress() * BAR_WIDTH as f64).round() as usize;
+                let mut line = vec![
+                    Span::styled(format!("{} ", layer.id), app.theme.commit_hash(false)),
+                    Span::raw(format!("[{}{}] ", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled))),
+                    Span::raw(format!("{:<20}", layer.status)),
+                ];
+                if layer.total > 0 && layer.progress() < 1.0 {
+                    line.push(Span::styled(
+                        format!("{} / {}", format_bytes(layer.current), format_bytes(layer.total)),
+                        app.theme.text(false, false),
+                    ));
+                }
+                Spans::from(line)
+            })
+            .collect();
+        // the newest layers are the interesting ones once the list doesn't fit
+        let hidden = layers.len().saturating_sub(chunks[2].height as usize);
+        f.render_widget(Paragraph::new(layers).scroll((hidden as u16, 0)), chunks[2]);
+
+        let footer = match &self.state {
+            PullState::Running => Span::styled(format!("{}  (esc: cancel)", self.message), app.theme.text(false, false)),
+            PullState::Done(message) => Span::styled(format!("{}  (enter: close)", message), app.theme.option(true)),
+            PullState::Failed(error) => Span::styled(format!("{}  (enter: close)", error), app.theme.text_danger()),
+        };
+        f.render_widget(Paragraph::new(Spans::from(footer)), chunks[3]);
+        Ok(())
+    }
+}
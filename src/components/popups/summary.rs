@@ -0,0 +1,73 @@
+use anyhow::Error;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::Rect;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::popups::{centered_rect, PopupAction};
+
+/// How each of the actions of a bulk action went, one line per item
+pub struct SummaryPopup {
+    title: String,
+    /// What was done to an item, or why it failed
+    results: Vec<Result<String, String>>,
+    scroll: u16,
+}
+
+impl SummaryPopup {
+    pub fn new(title: &str, results: Vec<Result<String, String>>) -> SummaryPopup {
+        let failed = results.iter().filter(|result| result.is_err()).count();
+        let title = if failed == 0 {
+            format!("{}: all {} done", title, results.len())
+        } else {
+            format!("{}: {} of {} failed", title, failed, results.len())
+        };
+        SummaryPopup {
+            title,
+            results,
+            scroll: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> PopupAction {
+        match key {
+            Key::Esc | Key::Char('\n') | Key::Char('q') => return PopupAction::Close,
+            Key::Down => self.scroll = self.scroll.saturating_add(1),
+            Key::Up => self.scroll = self.scroll.saturating_sub(1),
+            Key::PageDown => self.scroll = self.scroll.saturating_add(10),
+            Key::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            _ => {}
+        }
+        PopupAction::None
+    }
+}
+
+impl DrawableComponent for SummaryPopup {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let area = centered_rect(70, 60, rect);
+        // failures first, they are what needs looking at
+        let (failed, done): (Vec<_>, Vec<_>) = self.results.iter().partition(|result| result.is_err());
+        let text: Vec<Spans> = failed
+            .into_iter()
+            .chain(done)
+            .map(|result| match result {
+                Ok(done) => Spans::from(Span::styled(format!("✓ {}", done), app.theme.container_state("running"))),
+                Err(err) => Spans::from(Span::styled(format!("✗ {}", err), app.theme.text_danger())),
+            })
+            .collect();
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text)
+                .block(Block::default().borders(Borders::ALL).title(self.title.as_str()))
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            area,
+        );
+        Ok(())
+    }
+}
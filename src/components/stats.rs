@@ -0,0 +1,230 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::Error;
+use bollard::container::Stats;
+use termion::event::Key;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Constraint, Rect};
+use tui::text::Span;
+use tui::widgets::{Block, Borders, Row, Table, TableState};
+
+use crate::components::MutableDrawableComponent;
+use crate::components::main_app::MainApp;
+use crate::components::util::format_bytes;
+
+/// Number of samples kept for the charts, the daemon sends one per second
+pub const HISTORY: usize = 60;
+
+/// Figures of one stats sample, io counters are totals since the container started
+#[derive(Clone, Copy, Default)]
+pub struct Sample {
+    pub cpu_percent: f64,
+    pub memory_usage: u64,
+    pub memory_limit: u64,
+    pub net_rx: u64,
+    pub net_tx: u64,
+    pub block_read: u64,
+    pub block_write: u64,
+}
+
+impl Sample {
+    fn from_stats(stats: &Stats) -> Sample {
+        let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
+            - stats.precpu_stats.cpu_usage.total_usage as f64;
+        let system_delta = stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+            - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+        let online_cpus = stats.cpu_stats.online_cpus
+            .or_else(|| stats.cpu_stats.cpu_usage.percpu_usage.as_ref().map(|cpus| cpus.len() as u64))
+            .unwrap_or(1) as f64;
+        let cpu_percent = if cpu_delta > 0.0 && system_delta > 0.0 {
+            cpu_delta / system_delta * online_cpus * 100.0
+        } else {
+            0.0
+        };
+
+        // page cache is reclaimable, the docker cli leaves it out as well
+        let cache = stats.memory_stats.stats.map_or(0, |memory| memory.cache);
+        let memory_usage = stats.memory_stats.usage.unwrap_or(0).saturating_sub(cache);
+
+        let (net_rx, net_tx) = stats.networks
+            .iter()
+            .flat_map(|networks| networks.values())
+            .fold((0, 0), |(rx, tx), network| (rx + network.rx_bytes, tx + network.tx_bytes));
+
+        let (block_read, block_write) = stats.blkio_stats.io_service_bytes_recursive
+            .iter()
+            .flatten()
+            .fold((0, 0), |(read, write), entry| match entry.op.to_lowercase().as_str() {
+                "read" => (read + entry.value, write),
+                "write" => (read, write + entry.value),
+                _ => (read, write),
+            });
+
+        Sample {
+            cpu_percent,
+            memory_usage,
+            memory_limit: stats.memory_stats.limit.unwrap_or(0),
+            net_rx,
+            net_tx,
+            block_read,
+            block_write,
+        }
+    }
+
+    pub fn memory_percent(&self) -> f64 {
+        if self.memory_limit == 0 {
+            0.0
+        } else {
+            self.memory_usage as f64 / self.memory_limit as f64 * 100.0
+        }
+    }
+}
+
+/// Latest sample and recent history of a running container
+pub struct ContainerStats {
+    pub name: String,
+    pub latest: Sample,
+    pub cpu: VecDeque<f64>,
+    pub memory: VecDeque<f64>,
+    /// Bytes per sample received and sent over the network
+    pub net_rx_rate: VecDeque<u64>,
+    pub net_tx_rate: VecDeque<u64>,
+    /// Bytes per sample read from and written to block devices
+    pub block_rate: VecDeque<u64>,
+}
+
+impl ContainerStats {
+    pub fn new(name: &str) -> ContainerStats {
+        ContainerStats {
+            name: name.trim_start_matches('/').to_string(),
+            latest: Sample::default(),
+            cpu: VecDeque::with_capacity(HISTORY),
+            memory: VecDeque::with_capacity(HISTORY),
+            net_rx_rate: VecDeque::with_capacity(HISTORY),
+            net_tx_rate: VecDeque::with_capacity(HISTORY),
+            block_rate: VecDeque::with_capacity(HISTORY),
+        }
+    }
+
+    pub fn push(&mut self, stats: &Stats) {
+        let sample = Sample::from_stats(stats);
+        let previous = if self.cpu.is_empty() { sample } else { self.latest };
+
+        push_bounded(&mut self.cpu, sample.cpu_percent);
+        push_bounded(&mut self.memory, sample.memory_percent());
+        push_bounded(&mut self.net_rx_rate, sample.net_rx.saturating_sub(previous.net_rx));
+        push_bounded(&mut self.net_tx_rate, sample.net_tx.saturating_sub(previous.net_tx));
+        push_bounded(
+            &mut self.block_rate,
+            (sample.block_read + sample.block_write).saturating_sub(previous.block_read + previous.block_write),
+        );
+        self.latest = sample;
+    }
+}
+
+fn push_bounded<T>(values: &mut VecDeque<T>, value: T) {
+    if values.len() == HISTORY {
+        values.pop_front();
+    }
+    values.push_back(value);
+}
+
+/// Table of the running containers on the stats tab, the selection follows the container id
+pub struct StatsTable {
+    ids: Vec<String>,
+    selected_id: Option<String>,
+    state: TableState,
+    height: usize,
+}
+
+impl StatsTable {
+    pub fn new() -> StatsTable {
+        StatsTable {
+            ids: vec![],
+            selected_id: None,
+            state: TableState::default(),
+            height: 0,
+        }
+    }
+
+    pub fn selected_id(&self) -> Option<&str> {
+        self.selected_id.as_deref()
+    }
+
+    /// Rows ordered by name, the selection falls back to the first row
+    fn sync(&mut self, stats: &HashMap<String, ContainerStats>) {
+        let mut ids: Vec<&String> = stats.keys().collect();
+        ids.sort_by_key(|id| (&stats[*id].name, *id));
+        self.ids = ids.into_iter().cloned().collect();
+
+        let index = self.selected_id
+            .as_ref()
+            .and_then(|selected| self.ids.iter().position(|id| id == selected))
+            .or(if self.ids.is_empty() { None } else { Some(0) });
+        self.selected_id = index.map(|i| self.ids[i].clone());
+        self.state.select(index);
+    }
+
+    pub fn navigate(&mut self, key: Key) {
+        if self.ids.is_empty() {
+            return;
+        }
+        let last = self.ids.len() - 1;
+        let page = self.height.max(1);
+        let current = self.state.selected().unwrap_or(0);
+        let index = match key {
+            Key::Down => (current + 1).min(last),
+            Key::Up => current.saturating_sub(1),
+            Key::Home => 0,
+            Key::End => last,
+            Key::PageDown => (current + page).min(last),
+            Key::PageUp => current.saturating_sub(page),
+            _ => return,
+        };
+        self.state.select(Some(index));
+        self.selected_id = Some(self.ids[index].clone());
+    }
+}
+
+impl MutableDrawableComponent for StatsTable {
+    fn draw<B: Backend>(&mut self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        self.sync(&app.stats);
+        // borders, header and the gap below it
+        self.height = rect.height.saturating_sub(4) as usize;
+
+        let header = ["Name", "CPU %", "Mem usage / limit", "Mem %", "Net RX / TX", "Block R / W"];
+        let rows = self.ids.iter().map(|id| {
+            let stats = &app.stats[id];
+            let sample = &stats.latest;
+            Row::Data(vec![
+                stats.name.clone(),
+                format!("{:.2}%", sample.cpu_percent),
+                format!("{} / {}", format_bytes(sample.memory_usage), format_bytes(sample.memory_limit)),
+                format!("{:.2}%", sample.memory_percent()),
+                format!("{} / {}", format_bytes(sample.net_rx), format_bytes(sample.net_tx)),
+                format!("{} / {}", format_bytes(sample.block_read), format_bytes(sample.block_write)),
+            ].into_iter())
+        });
+
+        let widths = [
+            Constraint::Percentage(25),
+            Constraint::Percentage(10),
+            Constraint::Percentage(20),
+            Constraint::Percentage(10),
+            Constraint::Percentage(17),
+            Constraint::Percentage(18),
+        ];
+        let table = Table::new(header.iter(), rows)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.block(true))
+                .title(Span::styled("Running containers", app.theme.title(true))))
+            .header_style(app.theme.title(true))
+            .highlight_style(app.theme.text(true, true))
+            .highlight_symbol(">> ")
+            .widths(&widths);
+        f.render_stateful_widget(table, rect, &mut self.state);
+        Ok(())
+    }
+}
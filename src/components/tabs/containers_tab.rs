@@ -5,9 +5,6 @@ use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::widgets::{Block, Borders, Paragraph};
 
 use crate::components::{DrawableComponent, MutableDrawableComponent};
-use crate::components::images::Images;
-use crate::components::containers::Containers;
-use crate::components::volumes::Volumes;
 use crate::components::main_app::MainApp;
 
 pub struct ContainersTab();
@@ -30,27 +27,23 @@ impl DrawableComponent for ContainersTab {
             .constraints(
                     [
                         Constraint::Percentage(40),
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(30),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
                     ]
                     .as_ref(),
             )
             .split(right_chunks[0]);
 
-        f.render_widget(
-            Paragraph::new("logs value with some stuff")
-                .block(Block::default().borders(Borders::ALL).title("Logs"))
-                .alignment(Alignment::Left),
-            right_chunks[1]);
+        app.logs.draw(f, right_chunks[1], app)?;
 
-        let mut containers = Containers::new();
-        containers.draw(f, left_chunks[0], app)?;
+        app.container_list.borrow_mut().draw(f, left_chunks[0], app)?;
 
-        let mut images = Images::new();
-        images.draw(f, left_chunks[1], app)?;
+        app.image_list.borrow_mut().draw(f, left_chunks[1], app)?;
 
-        let mut volumes = Volumes::new();
-        volumes.draw(f, left_chunks[2], app)?;
+        app.volume_list.borrow_mut().draw(f, left_chunks[2], app)?;
+
+        app.network_list.borrow_mut().draw(f, left_chunks[3], app)?;
 
         Ok(())
     }
@@ -5,8 +5,12 @@ use anyhow::Error;
 use tui::Frame;
 use crate::components::main_app::MainApp;
 use containers_tab::ContainersTab;
+use stats_tab::StatsTab;
+use version_tab::VersionTab;
 
 mod containers_tab;
+mod stats_tab;
+mod version_tab;
 
 pub enum Tab {
     Containers,
@@ -32,8 +36,8 @@ impl DrawableComponent for Tab {
     fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
         match self {
             Tab::Containers => ContainersTab().draw(f, rect, app).unwrap(),
-            Tab::Stats => {}
-            Tab::Version => {}
+            Tab::Stats => StatsTab().draw(f, rect, app)?,
+            Tab::Version => VersionTab().draw(f, rect, app)?,
         };
         Ok(())
     }
@@ -0,0 +1,131 @@
+use anyhow::Error;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Style};
+use tui::symbols;
+use tui::text::Span;
+use tui::widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Sparkline};
+
+use crate::components::{DrawableComponent, MutableDrawableComponent};
+use crate::components::main_app::MainApp;
+use crate::components::stats::{ContainerStats, HISTORY};
+use crate::components::util::format_bytes;
+
+pub struct StatsTab();
+
+impl DrawableComponent for StatsTab {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(20),
+                ]
+                    .as_ref(),
+            )
+            .split(rect);
+
+        let mut table = app.stats_table.borrow_mut();
+        table.draw(f, chunks[0], app)?;
+
+        let selected = table.selected_id().and_then(|id| app.stats.get(id));
+        match selected {
+            Some(stats) => {
+                StatsTab::draw_charts(f, chunks[1], stats, app);
+                StatsTab::draw_sparklines(f, chunks[2], stats, app);
+            }
+            None => f.render_widget(
+                Paragraph::new("Waiting for stats of running containers...")
+                    .block(Block::default().borders(Borders::ALL))
+                    .alignment(Alignment::Center),
+                chunks[1],
+            ),
+        }
+        Ok(())
+    }
+}
+
+impl StatsTab {
+    fn draw_charts<B: Backend>(f: &mut Frame<B>, rect: Rect, stats: &ContainerStats, app: &MainApp) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(rect);
+
+        let cpu = to_points(stats.cpu.iter());
+        // a busy container can use more than one core
+        let cpu_max = stats.cpu.iter().cloned().fold(100.0, f64::max);
+        StatsTab::draw_chart(f, chunks[0], app, &format!("CPU {:.2}%", stats.latest.cpu_percent), &cpu, cpu_max, Color::Cyan);
+
+        let memory = to_points(stats.memory.iter());
+        let title = format!(
+            "Memory {} / {}",
+            format_bytes(stats.latest.memory_usage),
+            format_bytes(stats.latest.memory_limit)
+        );
+        StatsTab::draw_chart(f, chunks[1], app, &title, &memory, 100.0, Color::Magenta);
+    }
+
+    fn draw_chart<B: Backend>(f: &mut Frame<B>, rect: Rect, app: &MainApp, title: &str, data: &[(f64, f64)], max: f64, color: Color) {
+        let datasets = vec![Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(data)];
+
+        let chart = Chart::new(datasets)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(title, app.theme.title(true))))
+            .x_axis(Axis::default()
+                .style(app.theme.text(false, false))
+                .bounds([0.0, HISTORY as f64 - 1.0]))
+            .y_axis(Axis::default()
+                .style(app.theme.text(false, false))
+                .labels(vec![Span::raw("0%"), Span::raw(format!("{:.0}%", max))])
+                .bounds([0.0, max]));
+        f.render_widget(chart, rect);
+    }
+
+    fn draw_sparklines<B: Backend>(f: &mut Frame<B>, rect: Rect, stats: &ContainerStats, app: &MainApp) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(33),
+                    Constraint::Percentage(33),
+                    Constraint::Percentage(34),
+                ]
+                    .as_ref(),
+            )
+            .split(rect);
+
+        let lines = [
+            ("Net RX", &stats.net_rx_rate, Color::Green, chunks[0]),
+            ("Net TX", &stats.net_tx_rate, Color::Yellow, chunks[1]),
+            ("Block IO", &stats.block_rate, Color::Blue, chunks[2]),
+        ];
+        for (name, rates, color, area) in lines.iter() {
+            let data: Vec<u64> = rates.iter().cloned().collect();
+            let current = data.last().cloned().unwrap_or(0);
+            let sparkline = Sparkline::default()
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .title(Span::styled(format!("{} {}/s", name, format_bytes(current)), app.theme.title(true))))
+                .style(Style::default().fg(*color))
+                .data(&data);
+            f.render_widget(sparkline, *area);
+        }
+    }
+}
+
+/// History values as chart points, the oldest sample sits on the left
+fn to_points<'a>(values: impl Iterator<Item = &'a f64>) -> Vec<(f64, f64)> {
+    values
+        .enumerate()
+        .map(|(i, value)| (i as f64, *value))
+        .collect()
+}
@@ -0,0 +1,59 @@
+use anyhow::Error;
+use tui::backend::Backend;
+use tui::Frame;
+use tui::layout::{Alignment, Rect};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Wrap};
+
+use crate::components::DrawableComponent;
+use crate::components::main_app::MainApp;
+
+pub struct VersionTab();
+
+impl DrawableComponent for VersionTab {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled("Version (y: copy)", app.theme.title(true)));
+
+        let version = match &app.version {
+            Some(version) => version,
+            None => {
+                f.render_widget(
+                    Paragraph::new("Fetching version information...")
+                        .block(block)
+                        .alignment(Alignment::Center),
+                    rect,
+                );
+                return Ok(());
+            }
+        };
+
+        let width = version.sections
+            .iter()
+            .flat_map(|section| section.entries.iter())
+            .map(|(key, _)| key.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut text = vec![];
+        for section in &version.sections {
+            text.push(Spans::from(Span::styled(section.title, app.theme.title(true))));
+            for (key, value) in &section.entries {
+                text.push(Spans::from(vec![
+                    Span::styled(format!("  {:width$}  ", key, width = width), app.theme.text(false, false)),
+                    Span::raw(value.as_str()),
+                ]));
+            }
+            text.push(Spans::default());
+        }
+
+        f.render_widget(
+            Paragraph::new(text)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            rect,
+        );
+        Ok(())
+    }
+}
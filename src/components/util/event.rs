@@ -1,15 +1,22 @@
 use std::io;
+use std::io::Read;
+use std::os::unix::io::AsRawFd;
 use std::sync::mpsc;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, Condvar, Mutex,
 };
 use std::thread;
 use std::time::Duration;
 
+use mio::unix::EventedFd;
+use mio::{Poll, PollOpt, Ready, Token};
 use termion::event::Key;
 use termion::input::TermRead;
 
+/// How long the input thread waits for stdin before checking whether it was paused
+pub const INPUT_POLL: Duration = Duration::from_millis(50);
+
 #[derive(Debug)]
 pub enum Event<I> {
     Input(I),
@@ -20,6 +27,9 @@ pub enum Event<I> {
 /// type is handled in its own thread and returned to a common `Receiver`
 pub struct Events {
     rx: mpsc::Receiver<Event<Key>>,
+    paused: Arc<AtomicBool>,
+    /// Whether the input thread stopped reading stdin, confirmed back to `pause`
+    idle: Arc<(Mutex<bool>, Condvar)>,
     input_handle: thread::JoinHandle<()>,
     ignore_exit_key: Arc<AtomicBool>,
     tick_handle: thread::JoinHandle<()>,
@@ -48,27 +58,46 @@ impl Events {
     pub fn with_config(config: Config) -> Events {
         let (tx, rx) = mpsc::channel();
         let ignore_exit_key = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+        let idle = Arc::new((Mutex::new(false), Condvar::new()));
         let input_handle = {
             let tx = tx.clone();
             let ignore_exit_key = ignore_exit_key.clone();
+            let paused = paused.clone();
+            let idle = idle.clone();
             thread::spawn(move || {
-                let stdin = io::stdin();
-                for evt in stdin.keys() {
-                    if let Ok(key) = evt {
-                        if let Err(err) = tx.send(Event::Input(key)) {
-                            eprintln!("{}", err);
-                            return;
-                        }
-                        if !ignore_exit_key.load(Ordering::Relaxed) && key == config.exit_key {
-                            return;
+                let send = |key: Key| {
+                    if let Err(err) = tx.send(Event::Input(key)) {
+                        eprintln!("{}", err);
+                        return false;
+                    }
+                    ignore_exit_key.load(Ordering::Relaxed) || key != config.exit_key
+                };
+                match poll_stdin() {
+                    Ok(poll) => {
+                        read_polled(&poll, &paused, &idle, send);
+                        // the thread is done reading, don't leave `pause` waiting
+                        *idle.0.lock().unwrap() = true;
+                        idle.1.notify_all();
+                    }
+                    Err(err) => {
+                        // stdin can't be polled when it isn't a terminal, pausing won't work
+                        // so don't let `pause` wait for it
+                        log::warn!("failed to poll stdin: {}", err);
+                        *idle.0.lock().unwrap() = true;
+                        for key in io::stdin().keys().flatten() {
+                            if !send(key) {
+                                return;
+                            }
                         }
                     }
                 }
             })
         };
         let tick_handle = {
+            let paused = paused.clone();
             thread::spawn(move || loop {
-                if tx.send(Event::Tick).is_err() {
+                if !paused.load(Ordering::SeqCst) && tx.send(Event::Tick).is_err() {
                     break;
                 }
                 thread::sleep(config.tick_rate);
@@ -76,6 +105,8 @@ impl Events {
         };
         Events {
             rx,
+            paused,
+            idle,
             ignore_exit_key,
             input_handle,
             tick_handle,
@@ -93,4 +124,65 @@ impl Events {
     pub fn enable_exit_key(&mut self) {
         self.ignore_exit_key.store(false, Ordering::Relaxed);
     }
+
+    /// Stops reading stdin and sending ticks so another process can use the
+    /// terminal. Returns once the input thread is no longer reading.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        let (idle, confirmed) = &*self.idle;
+        let mut idle = idle.lock().unwrap();
+        while !*idle {
+            idle = confirmed.wait(idle).unwrap();
+        }
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+}
+
+pub fn poll_stdin() -> io::Result<Poll> {
+    let poll = Poll::new()?;
+    poll.register(&EventedFd(&io::stdin().as_raw_fd()), Token(0), Ready::readable(), PollOpt::level())?;
+    Ok(poll)
+}
+
+/// Only reads stdin while it has data and the input isn't paused. Whether it
+/// is paused is checked under the `idle` lock, which is released before
+/// polling, so `Events::pause` returns only once no read is in progress
+fn read_polled(poll: &Poll, paused: &AtomicBool, idle: &(Mutex<bool>, Condvar), mut send: impl FnMut(Key) -> bool) {
+    let mut events = mio::Events::with_capacity(1);
+    let mut buffer = [0; 1024];
+    loop {
+        {
+            let (idle, confirmed) = idle;
+            let mut idle = idle.lock().unwrap();
+            *idle = paused.load(Ordering::SeqCst);
+            if *idle {
+                confirmed.notify_all();
+                drop(idle);
+                thread::sleep(INPUT_POLL);
+                continue;
+            }
+        }
+        if let Err(err) = poll.poll(&mut events, Some(INPUT_POLL)) {
+            log::error!("failed to poll stdin: {}", err);
+            return;
+        }
+        if events.is_empty() {
+            continue;
+        }
+        // read everything that is available at once, stdin buffers internally
+        // and polling wouldn't see keys left in its buffer
+        let read = match io::stdin().read(&mut buffer) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Ok(0) | Err(_) => return,
+            Ok(read) => read,
+        };
+        for key in (&buffer[..read]).keys().flatten() {
+            if !send(key) {
+                return;
+            }
+        }
+    }
 }
\ No newline at end of file
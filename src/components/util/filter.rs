@@ -0,0 +1,210 @@
+use tui::style::Style;
+use tui::text::Span;
+
+/// Prefixes of the structured terms of a filter, any other `key:value` is an ordinary word
+const FIELDS: [&str; 10] = ["name", "id", "image", "status", "health", "project", "service", "label", "driver", "scope"];
+
+/// What a filter is matched against
+#[derive(Default)]
+pub struct Searchable<'a> {
+    /// Matched fuzzily by the words of the query, e.g. the name, ID and image
+    pub texts: Vec<&'a str>,
+    /// Matched by `key:value` terms, e.g. `("status", "exited")`
+    pub fields: Vec<(&'static str, &'a str)>,
+    /// Matched by `label:key=value` terms, the words of the query also match them as substrings
+    pub labels: Vec<(&'a str, &'a str)>,
+}
+
+/// A query typed after `/` in a pane. Every term has to match: words match
+/// fuzzily, `key:value` terms match the value of a field, e.g. `status:exited`,
+/// and `label:env=prod` or `label:env` match labels.
+#[derive(Default, Clone)]
+pub struct Filter {
+    query: String,
+    words: Vec<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Filter {
+    pub fn new(query: &str) -> Filter {
+        let mut words = vec![];
+        let mut fields = vec![];
+        for term in query.split_whitespace() {
+            let term = term.to_lowercase();
+            match term.find(':') {
+                Some(i) if FIELDS.contains(&&term[..i]) => fields.push((term[..i].to_string(), term[i + 1..].to_string())),
+                _ => words.push(term),
+            }
+        }
+        Filter {
+            query: query.to_string(),
+            words,
+            fields,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Title of a pane with the query after it, e.g. `Containers /web`
+    pub fn title(&self, title: &str) -> String {
+        if self.query.is_empty() {
+            title.to_string()
+        } else {
+            format!("{} /{}", title, self.query)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.fields.is_empty()
+    }
+
+    pub fn matches(&self, item: &Searchable) -> bool {
+        let words = self.words.iter().all(|word| {
+            item.texts.iter().any(|text| fuzzy_match(word, text).is_some())
+                || item.labels.iter().any(|(key, value)| {
+                    key.to_lowercase().contains(word.as_str()) || value.to_lowercase().contains(word.as_str())
+                })
+        });
+        words && self.fields.iter().all(|(key, value)| match key.as_str() {
+            "label" => {
+                let (key, value) = match value.find('=') {
+                    Some(i) => (&value[..i], Some(&value[i + 1..])),
+                    None => (value.as_str(), None),
+                };
+                item.labels.iter().any(|(label, label_value)| {
+                    label.to_lowercase() == key && value.map_or(true, |value| label_value.to_lowercase() == value)
+                })
+            }
+            _ => item.fields
+                .iter()
+                .any(|(field, field_value)| field == key && field_value.to_lowercase().contains(value.as_str())),
+        })
+    }
+
+    /// Char indices of `text` matched by the words of the query
+    pub fn highlights(&self, text: &str) -> Vec<usize> {
+        let mut positions: Vec<usize> = self.words
+            .iter()
+            .filter_map(|word| fuzzy_match(word, text))
+            .flatten()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Splits `text` into spans, the chars matched by the words of the query get `highlight` on top of `style`
+    pub fn spans(&self, text: String, style: Style, highlight: Style) -> Vec<Span<'static>> {
+        let positions = self.highlights(&text);
+        if positions.is_empty() {
+            return vec![Span::styled(text, style)];
+        }
+        let mut spans = vec![];
+        let mut current = String::new();
+        let mut matched = false;
+        for (i, c) in text.chars().enumerate() {
+            let is_match = positions.binary_search(&i).is_ok();
+            if is_match != matched && !current.is_empty() {
+                let style = if matched { style.patch(highlight) } else { style };
+                spans.push(Span::styled(std::mem::take(&mut current), style));
+            }
+            matched = is_match;
+            current.push(c);
+        }
+        let style = if matched { style.patch(highlight) } else { style };
+        spans.push(Span::styled(current, style));
+        spans
+    }
+}
+
+/// Char indices of `text` matching the lowercase `pattern` in order, ignoring case.
+/// A contiguous match is preferred over one spread out over the text.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Vec<usize>> {
+    // one char per char of the text so the indices line up with it
+    let lower: Vec<char> = text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    if pattern.is_empty() {
+        return Some(vec![]);
+    }
+    if let Some(start) = lower.windows(pattern.len()).position(|window| window == pattern.as_slice()) {
+        return Some((start..start + pattern.len()).collect());
+    }
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut pattern = pattern.into_iter().peekable();
+    for (i, c) in lower.into_iter().enumerate() {
+        if pattern.peek() == Some(&c) {
+            positions.push(i);
+            pattern.next();
+        }
+    }
+    Some(positions).filter(|_| pattern.peek().is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container<'a>(name: &'a str, status: &'a str, labels: Vec<(&'a str, &'a str)>) -> Searchable<'a> {
+        Searchable {
+            texts: vec![name],
+            fields: vec![("name", name), ("status", status)],
+            labels,
+        }
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_a_contiguous_match() {
+        assert_eq!(fuzzy_match("web", "wxexb-web"), Some(vec![6, 7, 8]));
+        assert_eq!(fuzzy_match("web", "wxexb"), Some(vec![0, 2, 4]));
+    }
+
+    #[test]
+    fn fuzzy_match_fails_when_a_char_is_missing() {
+        assert_eq!(fuzzy_match("wbz", "web"), None);
+        assert_eq!(fuzzy_match("bw", "web"), None);
+    }
+
+    #[test]
+    fn fuzzy_match_ignores_the_case_of_the_text() {
+        assert_eq!(fuzzy_match("web", "MyWeb"), Some(vec![2, 3, 4]));
+        assert!(Filter::new("WEB").matches(&container("my-web", "running", vec![])));
+    }
+
+    #[test]
+    fn status_term_matches_the_status_field() {
+        let filter = Filter::new("status:exited");
+        assert!(filter.matches(&container("db", "exited", vec![])));
+        assert!(!filter.matches(&container("db", "running", vec![])));
+        // a field term does not match the texts
+        assert!(!filter.matches(&container("exited", "running", vec![])));
+    }
+
+    #[test]
+    fn label_term_matches_key_and_value() {
+        let prod = container("web", "running", vec![("env", "prod")]);
+        let dev = container("web", "running", vec![("env", "dev")]);
+        assert!(Filter::new("label:env=prod").matches(&prod));
+        assert!(!Filter::new("label:env=prod").matches(&dev));
+        assert!(Filter::new("label:env").matches(&dev));
+        assert!(!Filter::new("label:tier").matches(&dev));
+    }
+
+    #[test]
+    fn unknown_prefix_is_a_plain_word() {
+        let filter = Filter::new("foo:bar");
+        assert!(!filter.is_empty());
+        assert!(filter.matches(&container("foo:bar", "running", vec![])));
+        assert!(!filter.matches(&container("foo", "running", vec![])));
+    }
+
+    #[test]
+    fn spans_highlight_the_matched_chars() {
+        let highlight = Style::default().add_modifier(tui::style::Modifier::BOLD);
+        let spans = Filter::new("eb").spans("web-1".to_string(), Style::default(), highlight);
+        let texts: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(texts, vec!["w", "eb", "-1"]);
+        assert_eq!(spans[1].style, highlight);
+    }
+}
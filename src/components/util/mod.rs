@@ -1,4 +1,5 @@
 pub mod event;
+pub mod filter;
 
 use rand::distributions::{Distribution, Uniform};
 use rand::rngs::ThreadRng;
@@ -6,6 +7,9 @@ use tui::widgets::ListState;
 pub use event::Config;
 use crate::components::tabs::Tab;
 use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
 pub struct RandomSignal {
@@ -82,9 +86,35 @@ impl TabsState {
     }
 }
 
+/// Identifies an item of a list across refreshes, e.g. by its docker object id
+pub trait Keyed {
+    fn key(&self) -> &str;
+}
+
+/// Drawn before an item, a dot when it is marked and blanks as wide when
+/// only others are, so the marked items stand out without shifting the rest
+pub fn mark_symbol(marked: bool, any_marked: bool) -> &'static str {
+    match (marked, any_marked) {
+        (true, _) => "● ",
+        (false, true) => "  ",
+        (false, false) => "",
+    }
+}
+
+/// Says how many of the marked items the filter or a toggle hides, e.g. ` (2 marked hidden)`
+pub fn hidden_marks_note(hidden: usize) -> String {
+    if hidden == 0 {
+        String::new()
+    } else {
+        format!(" ({} marked hidden)", hidden)
+    }
+}
+
 pub struct StatefulList<T> {
     pub state: ListState,
     pub items: Vec<T>,
+    /// Keys of the items marked for a bulk action, hidden ones included
+    marked: HashSet<String>,
 }
 
 impl<T> StatefulList<T> {
@@ -92,6 +122,7 @@ impl<T> StatefulList<T> {
         StatefulList {
             state: ListState::default(),
             items: Vec::new(),
+            marked: HashSet::new(),
         }
     }
 
@@ -99,10 +130,14 @@ impl<T> StatefulList<T> {
         StatefulList {
             state: ListState::default(),
             items,
+            marked: HashSet::new(),
         }
     }
 
     pub fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -117,6 +152,9 @@ impl<T> StatefulList<T> {
     }
 
     pub fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -130,7 +168,420 @@ impl<T> StatefulList<T> {
         self.state.select(Some(i));
     }
 
+    pub fn first(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn last(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.items.len() - 1));
+        }
+    }
+
+    /// Moves `n` items down without wrapping around
+    pub fn forward(&mut self, n: usize) {
+        if !self.items.is_empty() {
+            let i = self.state.selected().map_or(0, |i| i + n);
+            self.state.select(Some(i.min(self.items.len() - 1)));
+        }
+    }
+
+    /// Moves `n` items up without wrapping around
+    pub fn backward(&mut self, n: usize) {
+        if !self.items.is_empty() {
+            let i = self.state.selected().map_or(0, |i| i.saturating_sub(n));
+            self.state.select(Some(i));
+        }
+    }
+
     pub fn unselect(&mut self) {
         self.state.select(None);
     }
-}
\ No newline at end of file
+
+    pub fn selected(&self) -> Option<&T> {
+        self.state.selected().and_then(|i| self.items.get(i))
+    }
+
+    /// Replaces the items keeping the same object selected. When it is gone the
+    /// nearest of its former neighbours that is still there is selected instead.
+    /// Marks are kept, the items may only be hidden, see `retain_marks`.
+    pub fn set_items(&mut self, items: Vec<T>)
+    where
+        T: Keyed,
+    {
+        let selected = match self.state.selected() {
+            _ if items.is_empty() => None,
+            None => Some(0),
+            Some(selected) => {
+                let positions: HashMap<&str, usize> = items.iter().enumerate().map(|(i, item)| (item.key(), i)).collect();
+                // the selected item first, then the ones below and above it, nearest first
+                let old = &self.items;
+                let nearest = (0..old.len())
+                    .flat_map(|distance| vec![Some(selected + distance), selected.checked_sub(distance).filter(|_| distance > 0)])
+                    .flatten()
+                    .filter(|i| *i < old.len())
+                    .find_map(|i| positions.get(old[i].key()).copied());
+                Some(nearest.unwrap_or_else(|| selected.min(items.len() - 1)))
+            }
+        };
+        self.items = items;
+        self.state.select(selected);
+    }
+
+    /// Marks the selected item or unmarks it and moves on to the next one
+    pub fn toggle_mark(&mut self)
+    where
+        T: Keyed,
+    {
+        let key = match self.selected() {
+            Some(item) => item.key().to_string(),
+            None => return,
+        };
+        if !self.marked.remove(&key) {
+            self.marked.insert(key);
+        }
+        self.forward(1);
+    }
+
+    /// Marks every listed item, keeping the marks of hidden ones
+    pub fn mark_all(&mut self)
+    where
+        T: Keyed,
+    {
+        self.marked.extend(self.items.iter().map(|item| item.key().to_string()));
+    }
+
+    /// Marks the listed items that aren't and unmarks those that are, hidden ones stay as they are
+    pub fn invert_marks(&mut self)
+    where
+        T: Keyed,
+    {
+        for key in self.items.iter().map(Keyed::key) {
+            if !self.marked.remove(key) {
+                self.marked.insert(key.to_string());
+            }
+        }
+    }
+
+    /// Drops the marks of the objects that are gone, `all` is every object the
+    /// daemon listed rather than the items shown
+    pub fn retain_marks(&mut self, all: &[T])
+    where
+        T: Keyed,
+    {
+        let keys: HashSet<&str> = all.iter().map(Keyed::key).collect();
+        self.marked.retain(|key| keys.contains(key.as_str()));
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty()
+    }
+
+    pub fn is_marked(&self, item: &T) -> bool
+    where
+        T: Keyed,
+    {
+        self.marked.contains(item.key())
+    }
+
+    /// What to draw before each item, see `mark_symbol`
+    pub fn mark_symbols(&self) -> Vec<&'static str>
+    where
+        T: Keyed,
+    {
+        let any_marked = self.has_marks();
+        self.items.iter().map(|item| mark_symbol(self.is_marked(item), any_marked)).collect()
+    }
+
+    /// How many of the marked items aren't listed
+    pub fn hidden_marks(&self) -> usize
+    where
+        T: Keyed,
+    {
+        self.marked.len().saturating_sub(self.items.iter().filter(|item| self.is_marked(item)).count())
+    }
+
+    /// The marked objects of `all`, the hidden ones included
+    pub fn marked<'a>(&self, all: &'a [T]) -> Vec<&'a T>
+    where
+        T: Keyed,
+    {
+        all.iter().filter(|item| self.is_marked(item)).collect()
+    }
+}
+
+/// A key a list pane can be sorted by
+pub trait SortKey: Copy + PartialEq + 'static {
+    /// Every key, in the order they are cycled through
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+
+    /// Direction the key starts out in, e.g. newest or largest first
+    fn descending(self) -> bool;
+}
+
+/// Key a list pane is sorted by and the direction
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SortOrder<K> {
+    pub key: K,
+    pub descending: bool,
+}
+
+impl<K: SortKey> SortOrder<K> {
+    pub fn new(key: K) -> SortOrder<K> {
+        SortOrder {
+            key,
+            descending: key.descending(),
+        }
+    }
+
+    pub fn next_key(self) -> SortOrder<K> {
+        let index = K::ALL.iter().position(|key| *key == self.key).unwrap_or(0);
+        SortOrder::new(K::ALL[(index + 1) % K::ALL.len()])
+    }
+
+    pub fn reversed(self) -> SortOrder<K> {
+        SortOrder {
+            descending: !self.descending,
+            ..self
+        }
+    }
+
+    /// Turns the ascending order of two items into the order they are listed in
+    pub fn apply(self, ordering: Ordering) -> Ordering {
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// Shown in the title of the pane, e.g. `by size ▼`
+    pub fn label(self) -> String {
+        format!("by {} {}", self.key.name(), if self.descending { '▼' } else { '▲' })
+    }
+}
+
+/// Human readable size using binary units, e.g. `1.5MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[0])
+    } else {
+        format!("{:.1}{}", size, UNITS[unit])
+    }
+}
+
+/// How long ago a unix timestamp was, e.g. `3 days ago`
+pub fn format_age(timestamp: i64) -> String {
+    let seconds = (chrono::Utc::now().timestamp() - timestamp).max(0);
+    let (value, unit) = match seconds {
+        s if s < 60 => return "just now".to_string(),
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86400 => (s / 3600, "hour"),
+        s if s < 86400 * 30 => (s / 86400, "day"),
+        s if s < 86400 * 365 => (s / (86400 * 30), "month"),
+        s => (s / (86400 * 365), "year"),
+    };
+    format!("{} {}{} ago", value, unit, if value == 1 { "" } else { "s" })
+}
+
+/// The first 12 hex digits of an object id, without the `sha256:` prefix
+pub fn short_id(id: &str) -> &str {
+    let id = id.trim_start_matches("sha256:");
+    &id[..id.len().min(12)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Keyed for &'static str {
+        fn key(&self) -> &str {
+            self
+        }
+    }
+
+    fn list(items: Vec<&'static str>, selected: Option<usize>) -> StatefulList<&'static str> {
+        let mut list = StatefulList::with_items(items);
+        list.state.select(selected);
+        list
+    }
+
+    #[test]
+    fn set_items_follows_the_selected_object() {
+        let mut list = list(vec!["a", "b", "c"], Some(1));
+        list.set_items(vec!["c", "a", "b"]);
+        assert_eq!(list.selected(), Some(&"b"));
+    }
+
+    #[test]
+    fn set_items_selects_the_next_item_below_when_the_selected_one_is_gone() {
+        let mut list = list(vec!["a", "b", "c", "d"], Some(1));
+        list.set_items(vec!["d", "a", "c"]);
+        assert_eq!(list.selected(), Some(&"c"));
+    }
+
+    #[test]
+    fn set_items_selects_the_item_above_when_none_below_is_left() {
+        let mut list = list(vec!["a", "b", "c"], Some(1));
+        list.set_items(vec!["x", "a"]);
+        assert_eq!(list.selected(), Some(&"a"));
+    }
+
+    #[test]
+    fn set_items_keeps_the_position_when_no_former_neighbour_is_left() {
+        let mut list = list(vec!["a", "b", "c"], Some(2));
+        list.set_items(vec!["x", "y"]);
+        assert_eq!(list.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn set_items_selects_nothing_in_an_empty_list() {
+        let mut list = list(vec!["a", "b"], Some(1));
+        list.set_items(vec![]);
+        assert_eq!(list.state.selected(), None);
+        list.set_items(vec!["a"]);
+        assert_eq!(list.selected(), Some(&"a"));
+    }
+
+    #[test]
+    fn set_items_selects_the_first_item_when_none_was_selected() {
+        let mut list = list(vec!["a", "b"], None);
+        list.set_items(vec!["b", "a"]);
+        assert_eq!(list.selected(), Some(&"b"));
+    }
+
+    #[test]
+    fn format_bytes_picks_a_binary_unit() {
+        assert_eq!(format_bytes(0), "0B");
+        assert_eq!(format_bytes(1023), "1023B");
+        assert_eq!(format_bytes(1024), "1.0KiB");
+        assert_eq!(format_bytes(1536 * 1024), "1.5MiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024 * 1024 * 1024), "5120.0TiB");
+    }
+
+    #[test]
+    fn format_age_rounds_down_to_the_largest_unit() {
+        let ago = |seconds: i64| format_age(chrono::Utc::now().timestamp() - seconds);
+        assert_eq!(ago(5), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(3 * 3600 + 59), "3 hours ago");
+        assert_eq!(ago(86400 * 40), "1 month ago");
+        assert_eq!(ago(86400 * 365 * 2), "2 years ago");
+        // clocks out of step don't make it negative
+        assert_eq!(ago(-30), "just now");
+    }
+
+    #[test]
+    fn short_id_drops_the_digest_prefix() {
+        assert_eq!(short_id("sha256:0123456789abcdef0123"), "0123456789ab");
+        assert_eq!(short_id("0123456789abcdef"), "0123456789ab");
+        assert_eq!(short_id("abc"), "abc");
+        assert_eq!(short_id(""), "");
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum TestSort {
+        Name,
+        Size,
+    }
+
+    impl SortKey for TestSort {
+        const ALL: &'static [TestSort] = &[TestSort::Name, TestSort::Size];
+
+        fn name(self) -> &'static str {
+            match self {
+                TestSort::Name => "name",
+                TestSort::Size => "size",
+            }
+        }
+
+        fn descending(self) -> bool {
+            self == TestSort::Size
+        }
+    }
+
+    #[test]
+    fn sort_order_cycles_keys_in_their_own_direction() {
+        let sort = SortOrder::new(TestSort::Name);
+        assert!(!sort.descending);
+        let sort = sort.reversed().next_key();
+        assert_eq!(sort, SortOrder { key: TestSort::Size, descending: true });
+        assert_eq!(sort.next_key(), SortOrder::new(TestSort::Name));
+    }
+
+    #[test]
+    fn sort_order_applies_the_direction() {
+        let sort = SortOrder::new(TestSort::Name);
+        assert_eq!(sort.apply(Ordering::Less), Ordering::Less);
+        assert_eq!(sort.reversed().apply(Ordering::Less), Ordering::Greater);
+        assert_eq!(sort.label(), "by name ▲");
+        assert_eq!(sort.reversed().label(), "by name ▼");
+    }
+
+    fn marked(list: &StatefulList<&'static str>, all: &[&'static str]) -> Vec<&'static str> {
+        list.marked(all).into_iter().copied().collect()
+    }
+
+    #[test]
+    fn toggle_mark_marks_and_moves_on() {
+        let mut list = list(vec!["a", "b", "c"], Some(0));
+        list.toggle_mark();
+        assert_eq!(list.selected(), Some(&"b"));
+        list.toggle_mark();
+        assert_eq!(marked(&list, &["a", "b", "c"]), vec!["a", "b"]);
+        list.state.select(Some(0));
+        list.toggle_mark();
+        assert_eq!(marked(&list, &["a", "b", "c"]), vec!["b"]);
+    }
+
+    #[test]
+    fn mark_all_keeps_the_marks_of_hidden_items() {
+        let all = ["a", "b", "c", "d"];
+        let mut list = list(vec!["d"], Some(0));
+        list.toggle_mark();
+        list.set_items(vec!["a", "b"]);
+        list.mark_all();
+        assert_eq!(marked(&list, &all), vec!["a", "b", "d"]);
+        assert_eq!(list.hidden_marks(), 1);
+    }
+
+    #[test]
+    fn invert_marks_only_touches_listed_items() {
+        let all = ["a", "b", "c", "d"];
+        let mut list = list(vec!["a", "d"], Some(0));
+        list.mark_all();
+        list.set_items(vec!["a", "b", "c"]);
+        list.invert_marks();
+        assert_eq!(marked(&list, &all), vec!["b", "c", "d"]);
+        assert_eq!(list.hidden_marks(), 1);
+    }
+
+    #[test]
+    fn retain_marks_drops_the_marks_of_gone_objects() {
+        let mut list = list(vec!["a", "b"], Some(0));
+        list.mark_all();
+        list.set_items(vec![]);
+        list.retain_marks(&["b", "c"]);
+        assert_eq!(marked(&list, &["a", "b", "c"]), vec!["b"]);
+        assert_eq!(list.hidden_marks(), 1);
+        assert_eq!(hidden_marks_note(list.hidden_marks()), " (1 marked hidden)");
+        list.clear_marks();
+        assert!(!list.has_marks());
+        assert_eq!(hidden_marks_note(0), "");
+    }
+}
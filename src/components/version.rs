@@ -0,0 +1,119 @@
+use bollard::API_DEFAULT_VERSION;
+use bollard::system::Version;
+use serde_json::Value;
+
+use crate::components::util::format_bytes;
+
+pub struct Section {
+    pub title: &'static str,
+    pub entries: Vec<(&'static str, String)>,
+}
+
+/// Everything the version tab shows, kept as plain text so it can be copied as is
+pub struct VersionInfo {
+    pub sections: Vec<Section>,
+}
+
+impl VersionInfo {
+    pub fn new(host: &str, version: Result<Version, String>, info: Result<Value, String>) -> VersionInfo {
+        let client = Section {
+            title: "Client",
+            entries: vec![
+                ("dockerui", env!("CARGO_PKG_VERSION").to_string()),
+                ("API version", API_DEFAULT_VERSION.to_string()),
+                ("Endpoint", host.to_string()),
+            ],
+        };
+
+        let server = match version {
+            Ok(version) => {
+                let text = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+                Section {
+                    title: "Server",
+                    entries: vec![
+                        ("Version", text(version.version)),
+                        ("API version", format!(
+                            "{} (minimum {})",
+                            text(version.api_version),
+                            text(version.min_api_version)
+                        )),
+                        ("OS/Arch", format!("{}/{}", text(version.os), text(version.arch))),
+                        ("Kernel", text(version.kernel_version)),
+                        ("Go version", text(version.go_version)),
+                        ("Git commit", text(version.git_commit)),
+                        ("Built", text(version.build_time)),
+                    ],
+                }
+            }
+            Err(err) => Section {
+                title: "Server",
+                entries: vec![("Error", err)],
+            },
+        };
+
+        let system = match info {
+            Ok(info) => VersionInfo::system_section(&info),
+            Err(err) => Section {
+                title: "System",
+                entries: vec![("Error", err)],
+            },
+        };
+
+        VersionInfo {
+            sections: vec![client, server, system],
+        }
+    }
+
+    fn system_section(info: &Value) -> Section {
+        let text = |key: &str| match &info[key] {
+            Value::Null => "-".to_string(),
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        let list = |value: &Value| {
+            let items: Vec<String> = match value {
+                Value::Array(items) => items.iter().map(|item| item.as_str().map_or_else(|| item.to_string(), String::from)).collect(),
+                Value::Object(items) => items.keys().cloned().collect(),
+                _ => vec![],
+            };
+            if items.is_empty() { "-".to_string() } else { items.join(", ") }
+        };
+
+        Section {
+            title: "System",
+            entries: vec![
+                ("Name", text("Name")),
+                ("Operating system", text("OperatingSystem")),
+                ("CPUs", text("NCPU")),
+                ("Memory", info["MemTotal"].as_u64().map_or_else(|| "-".to_string(), format_bytes)),
+                ("Root dir", text("DockerRootDir")),
+                ("Storage driver", text("Driver")),
+                ("Cgroup driver", text("CgroupDriver")),
+                ("Cgroup version", text("CgroupVersion")),
+                ("Containers", format!(
+                    "{} ({} running, {} paused, {} stopped)",
+                    text("Containers"),
+                    text("ContainersRunning"),
+                    text("ContainersPaused"),
+                    text("ContainersStopped")
+                )),
+                ("Images", text("Images")),
+                ("Default runtime", text("DefaultRuntime")),
+                ("Runtimes", list(&info["Runtimes"])),
+                ("Security options", list(&info["SecurityOptions"])),
+                ("Registry mirrors", list(&info["RegistryConfig"]["Mirrors"])),
+            ],
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for section in &self.sections {
+            text.push_str(&format!("{}:\n", section.title));
+            for (key, value) in &section.entries {
+                text.push_str(&format!("  {}: {}\n", key, value));
+            }
+        }
+        text
+    }
+}
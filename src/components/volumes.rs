@@ -1,32 +1,69 @@
 use anyhow::Error;
-use bollard::models::{ImageSummary, Volume};
+use bollard::models::Volume;
 use bollard::service::ContainerSummaryInner;
-use itertools::Itertools;
 use tui::backend::Backend;
 use tui::Frame;
 use tui::layout::{Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, List, ListItem};
+use serde::{Deserialize, Serialize};
 
 use crate::components::{MutableDrawableComponent};
-use crate::components::main_app::MainApp;
-use crate::components::util::StatefulList;
+use crate::components::main_app::{MainApp, Pane};
+use crate::components::util::{format_bytes, hidden_marks_note, Keyed, SortKey, SortOrder, StatefulList};
+use crate::components::util::filter::{Filter, Searchable};
+
+/// What the volumes are sorted by
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum VolumeSort {
+    Name,
+    Size,
+}
+
+impl SortKey for VolumeSort {
+    const ALL: &'static [VolumeSort] = &[VolumeSort::Name, VolumeSort::Size];
+
+    fn name(self) -> &'static str {
+        match self {
+            VolumeSort::Name => "name",
+            VolumeSort::Size => "size",
+        }
+    }
+
+    fn descending(self) -> bool {
+        self == VolumeSort::Size
+    }
+}
 
 pub struct Volumes {
-    selected: usize,
     items: StatefulList<Volume>,
+    all: Vec<Volume>,
+    filter: Filter,
+    sort: SortOrder<VolumeSort>,
+    height: usize,
 }
+
+impl Keyed for Volume {
+    fn key(&self) -> &str {
+        &self.name
+    }
+}
+
 impl MutableDrawableComponent for Volumes {
     fn draw<B: Backend>(&mut self, f: &mut Frame<B>, rect: Rect, app: &MainApp) -> Result<(), Error> {
-        let mut names = Volumes::filter_names(app);
-        names.dedup();
+        self.height = rect.height.saturating_sub(2) as usize;
 
-        let items = Volumes::map_to_list_items(&names);
+        let items = Volumes::map_to_list_items(&self.items.items, &self.items.mark_symbols(), &self.filter, app);
 
-        //TODO items is 38 here :/
+        let focused = app.is_focused(Pane::Volumes);
+        let title = self.filter.title(&format!("Volumes {}", self.sort.label()));
+        let title = format!("{}{}", title, hidden_marks_note(self.items.hidden_marks()));
         let items = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Volumes"))
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.block(focused))
+                .title(Span::styled(title, app.theme.title(focused))))
             .highlight_style(
                 Style::default()
                     .bg(Color::LightGreen)
@@ -39,47 +76,153 @@ impl MutableDrawableComponent for Volumes {
 }
 
 impl Volumes {
-    pub fn new() -> Volumes {
+    pub fn new(sort: SortOrder<VolumeSort>) -> Volumes {
         Volumes {
-            selected: 0,
-            items: StatefulList::new()
+            items: StatefulList::new(),
+            all: vec![],
+            filter: Filter::default(),
+            sort,
+            height: 0,
         }
     }
 
+    pub fn set_items(&mut self, volumes: Vec<Volume>) {
+        self.all = volumes;
+        self.items.retain_marks(&self.all);
+        self.apply_filter();
+    }
 
-    fn filter_names(app: &MainApp) -> Vec<String> {
-        let names: Vec<String> = app.volumes.clone()
+    /// Keeps the volumes matching the filter, in the order they are drawn
+    fn apply_filter(&mut self) {
+        let filter = &self.filter;
+        let mut volumes: Vec<Volume> = self.all
             .iter()
-            .map(|i| {
-                let summary = i.clone();
-                let images = summary.labels.get_key_value("name");
-
-                match images {
-                    None => String::new(),
-                    Some(value) => {
-                        value.1.clone()
-                    }
-                }
-            })
-            .filter(|name| !name.is_empty())
+            .filter(|volume| filter.matches(&Volumes::searchable(volume)))
+            .cloned()
             .collect();
-        names
+        // the daemon reports -1 for a size it did not compute, those go with the smallest
+        let size = |volume: &Volume| volume.usage_data.as_ref().map_or(-1, |usage| usage.size);
+        let sort = self.sort;
+        volumes.sort_by(|a, b| {
+            let ordering = match sort.key {
+                VolumeSort::Name => a.name.cmp(&b.name),
+                VolumeSort::Size => size(a).cmp(&size(b)).then_with(|| a.name.cmp(&b.name)),
+            };
+            sort.apply(ordering)
+        });
+        self.items.set_items(volumes);
+    }
+
+    pub fn sort(&self) -> SortOrder<VolumeSort> {
+        self.sort
     }
 
-    fn map_to_list_items(names: &Vec<String>) -> Vec<ListItem> {
-        let items: Vec<ListItem> = names
+    pub fn set_sort(&mut self, sort: SortOrder<VolumeSort>) {
+        self.sort = sort;
+        self.apply_filter();
+    }
+
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = filter;
+        self.apply_filter();
+    }
+
+    pub fn selected(&self) -> Option<&Volume> {
+        self.items.selected()
+    }
+
+    pub fn list_mut(&mut self) -> &mut StatefulList<Volume> {
+        &mut self.items
+    }
+
+    pub fn has_marks(&self) -> bool {
+        self.items.has_marks()
+    }
+
+    /// The marked volumes, those the filter hides included
+    pub fn marked(&self) -> Vec<&Volume> {
+        self.items.marked(&self.all)
+    }
+
+    pub fn page_height(&self) -> usize {
+        self.height
+    }
+
+    /// Uses the reference count from system df when the daemon reported one,
+    /// otherwise looks for the volume in the mounts of the listed containers.
+    pub fn is_unused(volume: &Volume, app: &MainApp) -> bool {
+        match &volume.usage_data {
+            Some(usage) if usage.ref_count >= 0 => usage.ref_count == 0,
+            _ => Volumes::users(volume, &app.containers).is_empty(),
+        }
+    }
+
+    /// Listed containers that mount the volume. bollard reads the mounts of the
+    /// summary as mount specs, which drop the volume name the daemon sends, so
+    /// the source, the volume's mountpoint on the host, is what is left to match.
+    pub fn users<'a>(volume: &Volume, containers: &'a [ContainerSummaryInner]) -> Vec<&'a ContainerSummaryInner> {
+        containers
             .iter()
-            .map(|name| {
-                vec![(Spans::from(Span::styled(
-                    name,
-                    Style::default().add_modifier(Modifier::ITALIC),
-                )))]
+            .filter(|container| {
+                container.mounts.iter().flatten().any(|mount| mount.source.as_deref() == Some(volume.mountpoint.as_str()))
             })
-            .map(|mut lines| {
-                lines.dedup();
-                ListItem::new(lines).style(Style::default().fg(Color::Red))
+            .collect()
+    }
+
+    fn details(volume: &Volume) -> String {
+        let mut details = vec![volume.driver.clone()];
+        if let Some(scope) = volume.scope {
+            details.push(scope.to_string());
+        }
+        if let Some(usage) = &volume.usage_data {
+            // the daemon reports -1 when it did not compute the value
+            if usage.size >= 0 {
+                details.push(format_bytes(usage.size as u64));
+            }
+            if usage.ref_count >= 0 {
+                details.push(format!("{} ref{}", usage.ref_count, if usage.ref_count == 1 { "" } else { "s" }));
+            }
+        }
+        details.join(" ")
+    }
+
+    /// What the filter of the pane matches a volume against
+    fn searchable(volume: &Volume) -> Searchable<'_> {
+        let mut fields = vec![("name", volume.name.as_str()), ("driver", volume.driver.as_str())];
+        if let Some(scope) = &volume.scope {
+            fields.push(("scope", scope.as_ref()));
+        }
+        Searchable {
+            texts: vec![volume.name.as_str()],
+            fields,
+            labels: volume.labels.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect(),
+        }
+    }
+
+    fn map_to_list_items<'a>(volumes: &'a [Volume], marks: &[&'static str], filter: &Filter, app: &MainApp) -> Vec<ListItem<'a>> {
+        volumes
+            .iter()
+            .zip(marks)
+            .map(|(volume, mark)| {
+                let mut name = filter.spans(
+                    volume.name.clone(),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                    app.theme.filter_match(),
+                );
+                name.insert(0, Span::raw(*mark));
+                if Volumes::is_unused(volume, app) {
+                    name.push(Span::styled(" (unused)", app.theme.text(false, false)));
+                }
+                ListItem::new(vec![
+                    Spans::from(name),
+                    Spans::from(Span::styled(Volumes::details(volume), app.theme.text(false, false))),
+                    Spans::from(Span::styled(volume.mountpoint.as_str(), app.theme.text(false, false))),
+                ]).style(Style::default().fg(Color::Red))
             })
-            .collect();
-        items
+            .collect()
     }
-}
\ No newline at end of file
+}
@@ -1,58 +1,1130 @@
+use std::cmp::Reverse;
 use std::collections::hash_map::RandomState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::sync::{Arc};
 use std::thread::{Thread, yield_now};
 
-use bollard::container::ListContainersOptions;
-use bollard::Docker;
+use bollard::container::{KillContainerOptions, ListContainersOptions, LogsOptions, StatsOptions, RemoveContainerOptions, RestartContainerOptions, StartContainerOptions, StopContainerOptions};
+use anyhow::anyhow;
+use bollard::{API_DEFAULT_VERSION, Docker};
 use bollard::errors::Error;
-use bollard::image::ListImagesOptions;
-use bollard::service::{ContainerSummaryInner, ImageSummary, VolumeListResponse};
+use bollard::image::{CreateImageOptions, ListImagesOptions, PruneImagesOptions, RemoveImageOptions, TagImageOptions};
+use bollard::service::{ContainerConfig, ContainerSummaryInner, HistoryResponseItem, ImageSummary, Network, SystemEventsResponse, Volume, VolumeUsageData};
+use bollard::system::EventsOptions;
+use tokio::stream::StreamExt;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, Semaphore};
 use tokio::time::{Duration, Instant};
 
+use crate::components::logs::{LineSplitter, LogStream};
 use crate::components::main_app::MainApp;
+use crate::components::stats::ContainerStats;
+use crate::components::version::VersionInfo;
+use crate::components::containers::display_name;
+use crate::components::volumes::Volumes;
+use crate::components::popups::{ConfirmPopup, ImageDetailsPopup, InspectPopup, MessagePopup, Popup, PullState, SummaryPopup};
+use crate::components::util::{format_bytes, short_id};
 use tokio::sync::Mutex;
-use bollard::volume::ListVolumesOptions;
+use bollard::volume::{CreateVolumeOptions, ListVolumesOptions, PruneVolumesOptions, RemoveVolumeOptions};
+use bollard::network::{ConnectNetworkOptions, CreateNetworkOptions, DisconnectNetworkOptions, InspectNetworkOptions, ListNetworksOptions, PruneNetworksOptions};
+use crate::options::Options;
+use crate::terminal::Session;
+
+pub const DEFAULT_HOST: &str = "unix:///var/run/docker.sock";
+/// Read/write timeout in seconds for every request to the daemon
+const CLIENT_TIMEOUT: u64 = 120;
+/// How long to wait before subscribing to the event stream again after it dropped
+const EVENTS_RETRY: Duration = Duration::from_secs(2);
+/// How long to wait before restarting a stats stream that failed
+const STATS_RETRY: Duration = Duration::from_secs(2);
+/// How long the volume sizes and reference counts are reused before asking the daemon again
+const VOLUME_USAGE_TTL: Duration = Duration::from_secs(60);
+/// How many actions of a bulk action run at once
+const BULK_CONCURRENCY: usize = 8;
+
+/// Picks the endpoint to talk to, in order of precedence: the `--host` flag,
+/// the `DOCKER_HOST` environment variable, the config file and finally the
+/// default unix socket.
+pub fn resolve_host(flag: Option<String>, options: &Options) -> String {
+    flag.or_else(|| env::var("DOCKER_HOST").ok().filter(|host| !host.is_empty()))
+        .or_else(|| options.docker_host.clone())
+        .unwrap_or_else(|| DEFAULT_HOST.to_string())
+}
+
+pub fn get_client(host: &str) -> Result<Docker, anyhow::Error> {
+    if host.starts_with("unix://") {
+        connect_unix(host)
+    } else if host.starts_with("tcp://") {
+        Ok(Docker::connect_with_http(host, CLIENT_TIMEOUT, API_DEFAULT_VERSION)?)
+    } else {
+        Err(anyhow!("Unsupported docker host '{}', expected a unix:// or tcp:// url", host))
+    }
+}
+
+#[cfg(unix)]
+fn connect_unix(host: &str) -> Result<Docker, anyhow::Error> {
+    Ok(Docker::connect_with_unix(host, CLIENT_TIMEOUT, API_DEFAULT_VERSION)?)
+}
+
+#[cfg(not(unix))]
+fn connect_unix(host: &str) -> Result<Docker, anyhow::Error> {
+    Err(anyhow!("Unix sockets are not supported on this platform, cannot connect to '{}'", host))
+}
+
+/// Makes sure the daemon answers before the ui takes over the terminal
+pub async fn check_connection(host: &str) -> Result<(), anyhow::Error> {
+    get_client(host)?
+        .ping()
+        .await
+        .map_err(|err| anyhow!("Cannot connect to the Docker daemon at {}. Is the docker daemon running?\n{}", host, err))?;
+    Ok(())
+}
+
+/// bollard doesn't wrap `/info` yet so it is fetched with a bare hyper client, the
+/// payload is kept as json since newer daemons add fields the bollard models lack.
+pub async fn get_info(host: &str) -> Result<serde_json::Value, anyhow::Error> {
+    get_json(host, "/info").await
+}
+
+/// Calls that bollard doesn't cover are made with hyper on the same socket or address
+fn daemon_uri(host: &str, path: &str) -> Result<hyper::Uri, anyhow::Error> {
+    if host.starts_with("unix://") {
+        unix_uri(host.trim_start_matches("unix://"), path)
+    } else {
+        Ok(format!("http://{}{}", host.trim_start_matches("tcp://"), path).parse()?)
+    }
+}
+
+async fn send(host: &str, request: hyper::Request<hyper::Body>) -> Result<hyper::Response<hyper::Body>, anyhow::Error> {
+    if host.starts_with("unix://") {
+        send_unix(request).await
+    } else {
+        Ok(hyper::Client::new().request(request).await?)
+    }
+}
 
-// TODO: could be memoized or static
 #[cfg(unix)]
-fn get_client() -> Result<Docker, Error> {
-    let client = Docker::connect_with_unix_defaults();
-    client
+fn unix_uri(socket: &str, path: &str) -> Result<hyper::Uri, anyhow::Error> {
+    Ok(hyper_unix_connector::Uri::new(socket, path).into())
+}
+
+#[cfg(unix)]
+async fn send_unix(request: hyper::Request<hyper::Body>) -> Result<hyper::Response<hyper::Body>, anyhow::Error> {
+    let client: hyper::Client<_, hyper::Body> = hyper::Client::builder().build(hyper_unix_connector::UnixClient);
+    Ok(client.request(request).await?)
+}
+
+#[cfg(not(unix))]
+fn unix_uri(socket: &str, _path: &str) -> Result<hyper::Uri, anyhow::Error> {
+    Err(anyhow!("Unix sockets are not supported on this platform, cannot connect to '{}'", socket))
+}
+
+#[cfg(not(unix))]
+async fn send_unix(_request: hyper::Request<hyper::Body>) -> Result<hyper::Response<hyper::Body>, anyhow::Error> {
+    Err(anyhow!("Unix sockets are not supported on this platform"))
+}
+
+async fn get_json(host: &str, path: &str) -> Result<serde_json::Value, anyhow::Error> {
+    let request = hyper::Request::get(daemon_uri(host, path)?).body(hyper::Body::empty())?;
+    read_json(send(host, request).await?).await
+}
+
+async fn post_json(host: &str, path: &str, body: serde_json::Value) -> Result<serde_json::Value, anyhow::Error> {
+    let request = hyper::Request::post(daemon_uri(host, path)?)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(hyper::Body::from(body.to_string()))?;
+    read_json(send(host, request).await?).await
+}
+
+/// The response's json, or the daemon's message as the error when it failed
+async fn read_json(response: hyper::Response<hyper::Body>) -> Result<serde_json::Value, anyhow::Error> {
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    if !status.is_success() {
+        let message = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|body| body["message"].as_str().map(|message| format!(": {}", message)));
+        return Err(anyhow!("Docker responded with status code {}{}", status, message.unwrap_or_default()));
+    }
+    if body.is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Creates an exec of `command` with a tty in a running container. bollard's
+/// `CreateExecOptions` has no `Tty` field and a shell without one has no
+/// prompt or line editing, so this goes through hyper as well.
+pub async fn create_exec(host: &str, id: &str, command: &[String]) -> Result<String, anyhow::Error> {
+    let env: Vec<String> = env::var("TERM").map(|term| format!("TERM={}", term)).into_iter().collect();
+    let body = serde_json::json!({
+        "AttachStdin": true,
+        "AttachStdout": true,
+        "AttachStderr": true,
+        "Tty": true,
+        "Env": env,
+        "Cmd": command,
+    });
+    let exec = post_json(host, &format!("/containers/{}/exec", id), body).await?;
+    exec["Id"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| anyhow!("Docker didn't return the id of the exec"))
+}
+
+/// Starts a created exec, the daemon then hands over the connection for its tty
+pub async fn start_exec(host: &str, exec: &str) -> Result<hyper::upgrade::Upgraded, anyhow::Error> {
+    upgrade(host, &format!("/exec/{}/start", exec), Some(serde_json::json!({ "Detach": false, "Tty": true }))).await
 }
 
-pub async fn get_images() -> Result<Vec<ImageSummary>, Error> {
+/// Attaches to a container's main process, to what is typed as well when
+/// `stdin`. The daemon ends the stream when `detach_keys` are typed.
+pub async fn attach_container(
+    host: &str,
+    id: &str,
+    detach_keys: &str,
+    stdin: bool,
+) -> Result<hyper::upgrade::Upgraded, anyhow::Error> {
+    let path = format!(
+        "/containers/{}/attach?stream=1&stdout=1&stderr=1&stdin={}&detachKeys={}",
+        id, stdin as u8, detach_keys
+    );
+    upgrade(host, &path, None).await
+}
+
+/// The exit code of an exec that finished, `None` while it still runs
+pub async fn exec_exit_code(host: &str, exec: &str) -> Result<Option<i64>, anyhow::Error> {
+    Ok(get_json(host, &format!("/exec/{}/json", exec)).await?["ExitCode"].as_i64())
+}
+
+/// Resizes the tty of an exec or container, `path` is e.g. `/exec/<id>/resize`
+pub async fn resize_tty(host: &str, path: &str, (width, height): (u16, u16)) -> Result<(), anyhow::Error> {
+    let request = hyper::Request::post(daemon_uri(host, &format!("{}?h={}&w={}", path, height, width))?)
+        .body(hyper::Body::empty())?;
+    read_json(send(host, request).await?).await.map(|_| ())
+}
+
+/// Posts a request the daemon answers by hijacking the connection, e.g. starting
+/// an exec, and returns the raw connection the stream then runs over
+async fn upgrade(
+    host: &str,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<hyper::upgrade::Upgraded, anyhow::Error> {
+    let request = hyper::Request::post(daemon_uri(host, path)?)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .header(hyper::header::CONNECTION, "Upgrade")
+        .header(hyper::header::UPGRADE, "tcp")
+        .body(body.map_or_else(hyper::Body::empty, |body| hyper::Body::from(body.to_string())))?;
+    let response = send(host, request).await?;
+    if response.status() != hyper::StatusCode::SWITCHING_PROTOCOLS {
+        let status = response.status();
+        read_json(response).await?;
+        return Err(anyhow!("Docker didn't hand over the connection, it responded with status code {}", status));
+    }
+    Ok(response.into_body().on_upgrade().await?)
+}
+
+/// Fetches intermediate and dangling images as well, the images pane hides them
+/// until they are toggled on so switching doesn't need another round trip.
+pub async fn get_images(client: &Docker) -> Result<Vec<ImageSummary>, Error> {
     let filters: HashMap<&str, Vec<&str>, RandomState> = HashMap::new();
-    // let mut filters: HashMap<&str, Vec<&str>, RandomState> = HashMap::new();
-    // filters.insert("dangling", vec!["true"]);
 
     let options = Some(ListImagesOptions {
         all: true,
         filters,
         ..Default::default()
     });
-    get_client()?.list_images(options).await
+    client.list_images(options).await
 }
 
-pub async fn get_containers() -> Result<Vec<ContainerSummaryInner>, Error> {
+/// Fetches the stopped containers as well, the containers pane hides them
+/// until they are toggled on so switching doesn't need another round trip.
+pub async fn get_containers(client: &Docker) -> Result<Vec<ContainerSummaryInner>, Error> {
     let filters: HashMap<&str, Vec<&str>, RandomState> = HashMap::new();
 
     let options = Some(ListContainersOptions {
-        all: false,
+        all: true,
         filters,
         ..Default::default()
     });
-    get_client()?.list_containers(options).await
+    client.list_containers(options).await
 }
 
-pub async fn get_volumes() -> Result<VolumeListResponse, Error> {
+/// Layers of the image with the command that created them, the largest first
+pub async fn get_image_history(client: &Docker, id: &str) -> Result<Vec<HistoryResponseItem>, Error> {
+    let mut history = client.image_history(id).await?;
+    history.sort_by_key(|layer| Reverse(layer.size));
+    Ok(history)
+}
+
+/// Config the containers of the image start with: entrypoint, env, ports and so on
+pub async fn get_image_config(client: &Docker, id: &str) -> Result<Option<ContainerConfig>, Error> {
+    Ok(client.inspect_image(id).await?.config)
+}
+
+/// Lists the volumes together with the size and reference count in `usage`, when it has them
+pub async fn get_volumes(client: &Docker, usage: &HashMap<String, VolumeUsageData>) -> Result<Vec<Volume>, Error> {
     let filters: HashMap<&str, Vec<&str>, RandomState> = HashMap::new();
 
     let options = Some(ListVolumesOptions {
         filters,
         ..Default::default()
     });
-    get_client()?.list_volumes(options).await
+    let mut volumes = client.list_volumes(options).await?.volumes;
+    for volume in volumes.iter_mut() {
+        if let Some(data) = usage.get(&volume.name) {
+            volume.usage_data = Some(data.clone());
+        }
+    }
+    Ok(volumes)
+}
+
+/// Size and reference count of every volume by name. They come from
+/// `/system/df`, which is expensive for the daemon, so they are cached.
+pub async fn get_volume_usage(client: &Docker) -> Result<HashMap<String, VolumeUsageData>, Error> {
+    Ok(client.df()
+        .await?
+        .volumes
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|Volume { name, usage_data, .. }| usage_data.map(|data| (name, data)))
+        .collect())
+}
+
+/// Something to do to a single container
+#[derive(Debug, Clone)]
+pub enum ContainerAction {
+    Start,
+    Stop,
+    Restart,
+    Kill(String),
+    Pause,
+    Unpause,
+    Remove { force: bool, volumes: bool },
+}
+
+impl ContainerAction {
+    /// Past tense shown once the action went through
+    pub fn done(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => "Started",
+            ContainerAction::Stop => "Stopped",
+            ContainerAction::Restart => "Restarted",
+            ContainerAction::Kill(_) => "Killed",
+            ContainerAction::Pause => "Paused",
+            ContainerAction::Unpause => "Unpaused",
+            ContainerAction::Remove { .. } => "Removed",
+        }
+    }
+
+    /// Progressive tense shown while the action is running
+    pub fn pending(&self) -> &'static str {
+        match self {
+            ContainerAction::Start => "Starting",
+            ContainerAction::Stop => "Stopping",
+            ContainerAction::Restart => "Restarting",
+            ContainerAction::Kill(_) => "Killing",
+            ContainerAction::Pause => "Pausing",
+            ContainerAction::Unpause => "Unpausing",
+            ContainerAction::Remove { .. } => "Removing",
+        }
+    }
+}
+
+/// An object whose inspect payload can be shown
+#[derive(Debug, Clone)]
+pub enum InspectTarget {
+    Container(String),
+    Image(String),
+    Volume(String),
+    Network(String),
+}
+
+impl InspectTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            InspectTarget::Container(name)
+            | InspectTarget::Image(name)
+            | InspectTarget::Volume(name)
+            | InspectTarget::Network(name) => name,
+        }
+    }
+}
+
+/// Inspect payload of the target as a json tree
+pub async fn inspect(client: &Docker, target: &InspectTarget) -> Result<serde_json::Value, Error> {
+    let value = match target {
+        InspectTarget::Container(id) => serde_json::to_value(client.inspect_container(id, None).await?),
+        InspectTarget::Image(id) => serde_json::to_value(client.inspect_image(id).await?),
+        InspectTarget::Volume(name) => serde_json::to_value(client.inspect_volume(name).await?),
+        InspectTarget::Network(name) => {
+            serde_json::to_value(client.inspect_network(name, None::<InspectNetworkOptions<String>>).await?)
+        }
+    };
+    Ok(value?)
+}
+
+pub async fn run_container_action(client: &Docker, id: &str, action: &ContainerAction) -> Result<(), Error> {
+    match action {
+        ContainerAction::Start => client.start_container(id, None::<StartContainerOptions<String>>).await,
+        ContainerAction::Stop => client.stop_container(id, None::<StopContainerOptions>).await,
+        ContainerAction::Restart => client.restart_container(id, None::<RestartContainerOptions>).await,
+        ContainerAction::Kill(signal) => {
+            let options = Some(KillContainerOptions { signal: signal.as_str() });
+            client.kill_container(id, options).await
+        }
+        ContainerAction::Pause => client.pause_container(id).await,
+        ContainerAction::Unpause => client.unpause_container(id).await,
+        ContainerAction::Remove { force, volumes } => {
+            let options = Some(RemoveContainerOptions {
+                force: *force,
+                v: *volumes,
+                ..Default::default()
+            });
+            client.remove_container(id, options).await
+        }
+    }
+}
+
+/// Something to do to the images
+#[derive(Debug, Clone)]
+pub enum ImageAction {
+    Remove { id: String, name: String, force: bool, no_prune: bool },
+    /// Adds `target`, e.g. `registry/app:v2`, as another tag of the image
+    Tag { id: String, name: String, target: String },
+    /// Removes dangling images, or every image without a container when `all` is set
+    Prune { all: bool },
+}
+
+impl ImageAction {
+    /// Shown while the action runs, e.g. `Removing image nginx:latest`
+    pub fn pending(&self) -> String {
+        match self {
+            ImageAction::Remove { name, .. } => format!("Removing image {}", name),
+            ImageAction::Tag { name, target, .. } => format!("Tagging {} as {}", name, target),
+            ImageAction::Prune { all: false } => "Pruning dangling images".to_string(),
+            ImageAction::Prune { all: true } => "Pruning unused images".to_string(),
+        }
+    }
+}
+
+/// Runs the action, returns what to tell the user once it went through
+pub async fn run_image_action(client: &Docker, action: &ImageAction) -> Result<String, Error> {
+    match action {
+        ImageAction::Remove { id, name, force, no_prune } => {
+            let options = Some(RemoveImageOptions {
+                force: *force,
+                noprune: *no_prune,
+            });
+            let removed = client.remove_image(id, options, None).await?;
+            let deleted = removed.iter().filter(|item| item.deleted.is_some()).count();
+            Ok(format!("Removed image {}, deleted {} layer{}", name, deleted, if deleted == 1 { "" } else { "s" }))
+        }
+        ImageAction::Tag { id, name, target } => {
+            let (repo, tag) = split_reference(target);
+            client.tag_image(id, Some(TagImageOptions { repo, tag })).await?;
+            Ok(format!("Tagged {} as {}:{}", name, repo, tag))
+        }
+        ImageAction::Prune { all } => {
+            let mut filters = HashMap::new();
+            filters.insert("dangling", vec![if *all { "false" } else { "true" }]);
+            let pruned = client.prune_images(Some(PruneImagesOptions { filters })).await?;
+            let deleted = pruned.images_deleted
+                .unwrap_or_default()
+                .iter()
+                .filter(|item| item.deleted.is_some())
+                .count();
+            let reclaimed = format_bytes(pruned.space_reclaimed.unwrap_or(0).max(0) as u64);
+            Ok(format!("Pruned {} image layer{}, reclaimed {}", deleted, if deleted == 1 { "" } else { "s" }, reclaimed))
+        }
+    }
+}
+
+/// Images a prune would remove according to system df, which counts the
+/// containers of every image
+pub async fn prune_candidates(client: &Docker, all: bool) -> Result<Vec<ImageSummary>, Error> {
+    let images = client.df().await?.images.unwrap_or_default();
+    // dangling images are untagged leaves, an untagged parent of another image is a layer of it
+    let parents: HashSet<&str> = images.iter().map(|image| image.parent_id.as_str()).collect();
+    let candidates = images
+        .iter()
+        .filter(|image| image.containers == 0)
+        .filter(|image| {
+            all || (image.repo_tags.iter().all(|tag| tag == "<none>:<none>") && !parents.contains(image.id.as_str()))
+        })
+        .cloned()
+        .collect();
+    Ok(candidates)
+}
+
+/// The daemon refuses to remove an image a container uses or that has several
+/// tags without force, this says which of the listed containers is the reason
+fn image_conflict(app: &MainApp, id: &str, name: &str, message: &str) -> String {
+    let users: Vec<String> = app.containers
+        .iter()
+        .filter(|container| container.image_id.as_deref() == Some(id))
+        .map(display_name)
+        .collect();
+    let reason = if users.is_empty() {
+        format!("{} is used by a container or has several tags, remove it with force to do it anyway.", name)
+    } else {
+        format!("{} is used by {}, remove the container first or remove the image with force.", name, users.join(", "))
+    };
+    format!("{}\n\n{}", reason, message)
+}
+
+/// Something to do to the volumes
+#[derive(Debug, Clone)]
+pub enum VolumeAction {
+    /// An empty name lets the daemon pick one
+    Create { name: String, driver: String, driver_opts: HashMap<String, String>, labels: HashMap<String, String> },
+    Remove { name: String, force: bool },
+    Prune,
+}
+
+impl VolumeAction {
+    /// Shown while the action runs, e.g. `Removing volume data`
+    pub fn pending(&self) -> String {
+        match self {
+            VolumeAction::Create { name, .. } => format!("Creating volume {}", name),
+            VolumeAction::Remove { name, .. } => format!("Removing volume {}", name),
+            VolumeAction::Prune => "Pruning volumes".to_string(),
+        }
+    }
+}
+
+/// Runs the action, returns what to tell the user once it went through
+pub async fn run_volume_action(client: &Docker, action: &VolumeAction) -> Result<String, Error> {
+    match action {
+        VolumeAction::Create { name, driver, driver_opts, labels } => {
+            let options = CreateVolumeOptions {
+                name: name.clone(),
+                driver: driver.clone(),
+                driver_opts: driver_opts.clone(),
+                labels: labels.clone(),
+            };
+            let volume = client.create_volume(options).await?;
+            Ok(format!("Created volume {}", volume.name))
+        }
+        VolumeAction::Remove { name, force } => {
+            client.remove_volume(name, Some(RemoveVolumeOptions { force: *force })).await?;
+            Ok(format!("Removed volume {}", name))
+        }
+        VolumeAction::Prune => {
+            let pruned = client.prune_volumes(None::<PruneVolumesOptions<String>>).await?;
+            let deleted = pruned.volumes_deleted.unwrap_or_default().len();
+            let reclaimed = format_bytes(pruned.space_reclaimed.unwrap_or(0).max(0) as u64);
+            Ok(format!("Pruned {} volume{}, reclaimed {}", deleted, if deleted == 1 { "" } else { "s" }, reclaimed))
+        }
+    }
+}
+
+/// The daemon refuses to remove a volume a container still references, even a
+/// stopped one, this says which of the listed containers do
+fn volume_conflict(app: &MainApp, name: &str, message: &str) -> String {
+    let users: Vec<String> = app.volumes
+        .iter()
+        .find(|volume| volume.name == name)
+        .map(|volume| Volumes::users(volume, &app.containers).into_iter().map(display_name).collect())
+        .unwrap_or_default();
+    let reason = if users.is_empty() {
+        format!("{} is used by a container, remove the container first.", name)
+    } else {
+        format!("{} is used by {}, remove the containers first.", name, users.join(", "))
+    };
+    format!("{}\n\n{}", reason, message)
+}
+
+/// Every container, stopped ones included, using each of the volumes. The
+/// daemon matches them by volume name.
+pub async fn volume_users(client: &Docker, names: &[String]) -> Result<Vec<(String, Vec<ContainerSummaryInner>)>, Error> {
+    let mut users = Vec::with_capacity(names.len());
+    for name in names {
+        let mut filters = HashMap::new();
+        filters.insert("volume", vec![name.as_str()]);
+        let options = Some(ListContainersOptions {
+            all: true,
+            filters,
+            ..Default::default()
+        });
+        users.push((name.clone(), client.list_containers(options).await?));
+    }
+    Ok(users)
+}
+
+/// Asks to confirm removing the volumes that no container uses. The daemon
+/// refuses to remove one a container references, even a stopped one and even
+/// with force, so those are left out and the containers to remove first are named.
+fn volume_remove_popup(users: Vec<(String, Vec<ContainerSummaryInner>)>) -> Popup {
+    let (in_use, unused): (Vec<_>, Vec<_>) = users.into_iter().partition(|(_, users)| !users.is_empty());
+    let in_use: Vec<String> = in_use
+        .into_iter()
+        .map(|(name, users)| {
+            let users: Vec<String> = users
+                .into_iter()
+                .map(|container| format!("{} ({})", display_name(&container), container.state.as_deref().unwrap_or_default()))
+                .collect();
+            format!("  {}, used by {}", name, users.join(", "))
+        })
+        .collect();
+    let names: Vec<String> = unused.into_iter().map(|(name, _)| name).collect();
+
+    if names.is_empty() {
+        let mut lines = vec![format!(
+            "{} in use, remove these containers first:",
+            if in_use.len() == 1 { "The volume is" } else { "The volumes are" }
+        )];
+        lines.extend(in_use);
+        return Popup::Message(MessagePopup::info("Volume in use", lines));
+    }
+    let mut lines = vec![match names.as_slice() {
+        [name] => format!("Remove volume {}?", name),
+        names => format!("Remove these {} volumes?", names.len()),
+    }];
+    if names.len() > 1 {
+        lines.extend(names.iter().map(|name| format!("  {}", name)));
+    }
+    if !in_use.is_empty() {
+        lines.push("These are kept, their containers have to be removed first:".to_string());
+        lines.extend(in_use);
+    }
+    let title = if names.len() == 1 { "Remove volume" } else { "Remove volumes" };
+    Popup::Confirm(ConfirmPopup::new(
+        title,
+        lines,
+        vec![],
+        Box::new(move |_| match names.as_slice() {
+            [name] => IOEvent::Volume(VolumeAction::Remove { name: name.clone(), force: false }),
+            names => IOEvent::Bulk {
+                title: "Removing the marked volumes".to_string(),
+                actions: names
+                    .iter()
+                    .map(|name| BulkAction::Volume(VolumeAction::Remove { name: name.clone(), force: false }))
+                    .collect(),
+            },
+        }),
+    ))
+}
+
+/// Lists the networks with the containers attached to them, which only the
+/// inspect endpoint reports
+pub async fn get_networks(client: &Docker) -> Result<Vec<Network>, Error> {
+    let networks = client.list_networks(None::<ListNetworksOptions<String>>).await?;
+    // inspected all at once, one after the other adds up with many networks
+    let handles: Vec<_> = networks
+        .into_iter()
+        .map(|network| {
+            let client = client.clone();
+            let id = network.id.unwrap_or_default();
+            tokio::spawn(async move { client.inspect_network(&id, None::<InspectNetworkOptions<String>>).await })
+        })
+        .collect();
+    let mut inspected = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(Ok(network)) => inspected.push(network),
+            // listed again on the next refresh
+            Err(err) => log::error!("The task to inspect a network did not finish, {}", err),
+            // removed in the meantime
+            Ok(Err(Error::DockerResponseNotFoundError { .. })) => {}
+            Ok(Err(err)) => return Err(err),
+        }
+    }
+    Ok(inspected)
+}
+
+/// Something to do to the networks
+#[derive(Debug, Clone)]
+pub enum NetworkAction {
+    Create { name: String },
+    Remove { id: String, name: String },
+    Prune,
+    Connect { network: String, container: String },
+    Disconnect { network: String, container: String },
+}
+
+impl NetworkAction {
+    /// Shown while the action runs, e.g. `Connecting web to backend`
+    pub fn pending(&self) -> String {
+        match self {
+            NetworkAction::Create { name } => format!("Creating network {}", name),
+            NetworkAction::Remove { name, .. } => format!("Removing network {}", name),
+            NetworkAction::Prune => "Pruning networks".to_string(),
+            NetworkAction::Connect { network, container } => format!("Connecting {} to {}", container, network),
+            NetworkAction::Disconnect { network, container } => format!("Disconnecting {} from {}", container, network),
+        }
+    }
+}
+
+/// Runs the action, returns what to tell the user once it went through
+pub async fn run_network_action(client: &Docker, action: &NetworkAction) -> Result<String, Error> {
+    match action {
+        NetworkAction::Create { name } => {
+            let options = CreateNetworkOptions {
+                name: name.as_str(),
+                check_duplicate: true,
+                driver: "bridge",
+                ..Default::default()
+            };
+            client.create_network(options).await?;
+            Ok(format!("Created network {}", name))
+        }
+        NetworkAction::Remove { id, name } => {
+            client.remove_network(id).await?;
+            Ok(format!("Removed network {}", name))
+        }
+        NetworkAction::Prune => {
+            let pruned = client.prune_networks(None::<PruneNetworksOptions<String>>).await?;
+            let pruned = pruned.networks_deleted.unwrap_or_default();
+            Ok(match pruned.len() {
+                0 => "No unused networks to prune".to_string(),
+                count => format!("Pruned {} network{}: {}", count, if count == 1 { "" } else { "s" }, pruned.join(", ")),
+            })
+        }
+        NetworkAction::Connect { network, container } => {
+            let options = ConnectNetworkOptions {
+                container: container.as_str(),
+                ..Default::default()
+            };
+            client.connect_network(network, options).await?;
+            Ok(format!("Connected {} to {}", container, network))
+        }
+        NetworkAction::Disconnect { network, container } => {
+            let options = DisconnectNetworkOptions {
+                container: container.as_str(),
+                force: false,
+            };
+            client.disconnect_network(network, options).await?;
+            Ok(format!("Disconnected {} from {}", container, network))
+        }
+    }
+}
+
+/// One of the actions of a bulk action, e.g. stopping one of the marked containers
+#[derive(Debug, Clone)]
+pub enum BulkAction {
+    Container { id: String, name: String, action: ContainerAction },
+    Image(ImageAction),
+    Volume(VolumeAction),
+    Network(NetworkAction),
+}
+
+impl BulkAction {
+    pub fn pending(&self) -> String {
+        match self {
+            BulkAction::Container { name, action, .. } => format!("{} {}", action.pending(), name),
+            BulkAction::Image(action) => action.pending(),
+            BulkAction::Volume(action) => action.pending(),
+            BulkAction::Network(action) => action.pending(),
+        }
+    }
+
+    /// The lists to fetch again once the action ran. Removing volumes or containers
+    /// changes the sizes and reference counts of the volumes, so their cached usage goes too.
+    fn refreshes(&self) -> Vec<IOEvent> {
+        match self {
+            BulkAction::Container { action: ContainerAction::Remove { .. }, .. } => {
+                vec![IOEvent::RefreshContainers, IOEvent::VolumeUsageChanged, IOEvent::RefreshVolumes]
+            }
+            BulkAction::Container { .. } => vec![IOEvent::RefreshContainers],
+            BulkAction::Image(_) => vec![IOEvent::RefreshImages],
+            BulkAction::Volume(_) => vec![IOEvent::VolumeUsageChanged, IOEvent::RefreshVolumes],
+            BulkAction::Network(_) => vec![IOEvent::RefreshNetworks],
+        }
+    }
+}
+
+/// Runs the action, returns what to tell the user once it went through
+pub async fn run_bulk_action(client: &Docker, action: &BulkAction) -> Result<String, Error> {
+    match action {
+        BulkAction::Container { id, name, action } => {
+            run_container_action(client, id, action).await?;
+            Ok(format!("{} {}", action.done(), name))
+        }
+        BulkAction::Image(action) => run_image_action(client, action).await,
+        BulkAction::Volume(action) => run_volume_action(client, action).await,
+        BulkAction::Network(action) => run_network_action(client, action).await,
+    }
+}
+
+/// Runs the actions of a bulk action a few at a time without holding up the IO
+/// worker, then shows how each went and refreshes the lists. A daemon that went
+/// away is noticed by those refreshes.
+async fn run_bulk(
+    app: Arc<Mutex<MainApp>>,
+    client: Docker,
+    io_tx: UnboundedSender<IOEvent>,
+    title: String,
+    actions: Vec<BulkAction>,
+) {
+    let semaphore = Arc::new(Semaphore::new(BULK_CONCURRENCY));
+    let handles: Vec<_> = actions
+        .iter()
+        .map(|action| {
+            let client = client.clone();
+            let action = action.clone();
+            let semaphore = Arc::clone(&semaphore);
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await;
+                run_bulk_action(&client, &action).await
+            })
+        })
+        .collect();
+    let mut results = Vec::with_capacity(actions.len());
+    for (action, handle) in actions.iter().zip(handles) {
+        let result = match handle.await {
+            Ok(Ok(done)) => Ok(done),
+            Ok(Err(err)) => {
+                log::error!("Failed to {:?}, {}", action, err);
+                Err(format!("{} failed, {}", action.pending(), err))
+            }
+            Err(err) => {
+                log::error!("The task to {:?} did not finish, {}", action, err);
+                Err(format!("{} did not finish, {}", action.pending(), err))
+            }
+        };
+        results.push(result);
+    }
+    {
+        let mut app = app.lock().await;
+        app.notice = None;
+        app.popup = Some(Popup::Summary(SummaryPopup::new(&title, results)));
+    }
+    let mut refreshes: Vec<IOEvent> = vec![];
+    for event in actions.iter().flat_map(BulkAction::refreshes) {
+        if !refreshes.iter().any(|refresh| std::mem::discriminant(refresh) == std::mem::discriminant(&event)) {
+            refreshes.push(event);
+        }
+    }
+    for event in refreshes {
+        if let Err(err) = io_tx.send(event) {
+            log::error!("Failed to send the message to refresh the lists, {}", err)
+        }
+    }
+}
+
+/// Whether the IO worker can currently reach the daemon
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Down(String),
+}
+
+impl ConnectionState {
+    pub fn is_connected(&self) -> bool {
+        *self == ConnectionState::Connected
+    }
+}
+
+/// Errors raised before the daemon produced a response, as opposed to the daemon
+/// rejecting a request
+fn is_connection_error(err: &Error) -> bool {
+    matches!(err,
+        Error::HyperResponseError { .. } | Error::HttpClientError { .. } | Error::IOError { .. } | Error::RequestTimeoutError)
+}
+
+async fn set_connection(app: &Arc<Mutex<MainApp>>, current: &mut ConnectionState, state: ConnectionState) {
+    if *current != state {
+        log::debug!("Connection state changed to {:?}", state);
+        *current = state.clone();
+        app.lock().await.connection = state;
+    }
+}
+
+/// Marks the connection as down when `err` means the daemon could not be reached
+async fn track_error(app: &Arc<Mutex<MainApp>>, connection: &mut ConnectionState, err: &Error) {
+    if is_connection_error(err) {
+        set_connection(app, connection, ConnectionState::Down(err.to_string())).await;
+    }
+}
+
+/// Tries to get back in touch with the daemon, the client is rebuilt so no
+/// stale pooled connections are reused.
+async fn reconnect(app: &Arc<Mutex<MainApp>>, connection: &mut ConnectionState, client: &mut Docker, host: &str) {
+    set_connection(app, connection, ConnectionState::Reconnecting).await;
+    let state = match get_client(host) {
+        Ok(new_client) => match new_client.ping().await {
+            Ok(_) => {
+                *client = new_client;
+                ConnectionState::Connected
+            }
+            Err(err) => ConnectionState::Down(err.to_string()),
+        },
+        Err(err) => ConnectionState::Down(err.to_string()),
+    };
+    set_connection(app, connection, state).await;
+}
+
+/// Maps a daemon event to the lists that need to be fetched again
+fn refreshes_for(event: &SystemEventsResponse) -> Vec<IOEvent> {
+    let action = event.action.as_deref().unwrap_or_default();
+    match event.typ.as_deref() {
+        Some("container") => {
+            // exec and attach chatter (health checks run execs every few seconds) doesn't change the list
+            let noise = action.starts_with("exec_")
+                || ["attach", "resize", "top", "archive-path", "extract-to-dir", "export"].contains(&action);
+            match action {
+                _ if noise => vec![],
+                // the container takes or drops references to its volumes
+                "create" | "destroy" => {
+                    vec![IOEvent::RefreshContainers, IOEvent::VolumeUsageChanged, IOEvent::RefreshVolumes]
+                }
+                _ => vec![IOEvent::RefreshContainers],
+            }
+        }
+        Some("image") => vec![IOEvent::RefreshImages],
+        Some("volume") => vec![IOEvent::VolumeUsageChanged, IOEvent::RefreshVolumes],
+        // a container joining or leaving a network changes its summary
+        Some("network") if action == "connect" || action == "disconnect" => {
+            vec![IOEvent::RefreshContainers, IOEvent::RefreshNetworks]
+        }
+        Some("network") => vec![IOEvent::RefreshNetworks],
+        _ => vec![],
+    }
+}
+
+fn refresh_all(io_tx: &UnboundedSender<IOEvent>) -> bool {
+    vec![IOEvent::RefreshContainers, IOEvent::RefreshImages, IOEvent::RefreshVolumes, IOEvent::RefreshNetworks]
+        .into_iter()
+        .all(|event| io_tx.send(event).is_ok())
+}
+
+/// Follows the daemon's `/events` stream and requests targeted refreshes for
+/// whatever changed. While the stream is down the ui falls back to polling.
+async fn watch_events(app: Arc<Mutex<MainApp>>, io_tx: UnboundedSender<IOEvent>, host: String) {
+    loop {
+        match get_client(&host) {
+            Ok(client) => {
+                if client.ping().await.is_ok() {
+                    app.lock().await.events_live = true;
+                    // catch up on anything that happened while we weren't listening
+                    if !refresh_all(&io_tx) {
+                        return;
+                    }
+
+                    let mut events = client.events(None::<EventsOptions<String>>);
+                    while let Some(event) = events.next().await {
+                        match event {
+                            Ok(event) => {
+                                log::debug!("Docker event: {:?}", event);
+                                for refresh in refreshes_for(&event) {
+                                    if io_tx.send(refresh).is_err() {
+                                        return;
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                log::error!("The docker event stream failed, {}", err);
+                                break;
+                            }
+                        }
+                    }
+                    log::warn!("The docker event stream dropped, falling back to polling");
+                    app.lock().await.events_live = false;
+                }
+            }
+            Err(err) => log::error!("Failed to create the docker client for events, {}", err),
+        }
+        tokio::time::delay_for(EVENTS_RETRY).await;
+    }
+}
+
+/// Streams the output of a container into the logs pane until told to stop
+async fn stream_logs(app: Arc<Mutex<MainApp>>, client: Docker, id: String, tail: usize, mut stop: oneshot::Receiver<()>) {
+    let options = Some(LogsOptions {
+        follow: true,
+        stdout: true,
+        stderr: true,
+        timestamps: true,
+        tail: tail.to_string(),
+        ..Default::default()
+    });
+    let mut logs = client.logs(&id, options);
+    let mut splitter = LineSplitter::default();
+
+    loop {
+        tokio::select! {
+            _ = &mut stop => return,
+            output = logs.next() => match output {
+                Some(Ok(output)) => {
+                    let lines = splitter.push(output);
+                    if !lines.is_empty() {
+                        app.lock().await.logs.push(&id, lines);
+                    }
+                }
+                Some(Err(err)) => {
+                    log::error!("Failed to follow the logs of {}, {}", id, err);
+                    let mut app = app.lock().await;
+                    app.logs.push(&id, splitter.flush());
+                    app.logs.end(&id, &format!("-- failed to follow logs: {} --", err));
+                    return;
+                }
+                None => {
+                    let mut app = app.lock().await;
+                    app.logs.push(&id, splitter.flush());
+                    app.logs.end(&id, "-- log stream ended --");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Splits `nginx:1.19` into the image and its tag. Without a tag the daemon
+/// would pull every tag so `latest` is assumed, a digest stays part of the image.
+fn split_reference(reference: &str) -> (&str, &str) {
+    if reference.contains('@') {
+        return (reference, "");
+    }
+    let name_start = reference.rfind('/').map_or(0, |i| i + 1);
+    match reference[name_start..].rfind(':') {
+        Some(i) => (&reference[..name_start + i], &reference[name_start + i + 1..]),
+        None => (reference, "latest"),
+    }
+}
+
+/// Pulls an image and reports the progress to the pull popup. Dropping the
+/// stream when told to stop closes the request, which cancels the pull.
+async fn stream_pull(
+    app: Arc<Mutex<MainApp>>,
+    client: Docker,
+    io_tx: UnboundedSender<IOEvent>,
+    reference: String,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let (image, tag) = split_reference(&reference);
+    let options = Some(CreateImageOptions {
+        from_image: image,
+        tag,
+        ..Default::default()
+    });
+    let mut pull = client.create_image(options, None, None);
+    // the daemon reports a failed pull in the stream, which still ends normally,
+    // kept here so it isn't lost when the popup was closed meanwhile
+    let mut error: Option<String> = None;
+    let state = loop {
+        tokio::select! {
+            _ = &mut stop => return,
+            info = pull.next() => match info {
+                Some(Ok(info)) => {
+                    if let (None, Some(err)) = (&error, &info.error) {
+                        log::error!("Failed to pull {}, {}", reference, err);
+                        error = Some(err.clone());
+                    }
+                    if let Some(Popup::Pull(popup)) = app.lock().await.popup.as_mut() {
+                        popup.update(&info);
+                    }
+                }
+                Some(Err(err)) => {
+                    log::error!("Failed to pull {}, {}", reference, err);
+                    break PullState::Failed(error.unwrap_or_else(|| err.to_string()));
+                }
+                None => break match error {
+                    Some(err) => PullState::Failed(err),
+                    None => PullState::Done(format!("Pulled {}", reference)),
+                },
+            }
+        }
+    };
+
+    let mut app = app.lock().await;
+    match app.popup.as_mut() {
+        Some(Popup::Pull(popup)) if popup.reference() == reference => popup.finish(state),
+        _ => {
+            app.notice = Some(match state {
+                PullState::Failed(err) => format!("Pulling {} failed: {}", reference, err),
+                _ => format!("Pulled {}", reference),
+            })
+        }
+    }
+    if let Err(err) = io_tx.send(IOEvent::RefreshImages) {
+        log::error!("Failed to send the message to refresh images, {}", err)
+    }
+}
+
+/// Feeds the stats of a running container to the stats tab until told to stop
+async fn stream_stats(
+    app: Arc<Mutex<MainApp>>,
+    client: Docker,
+    io_tx: UnboundedSender<IOEvent>,
+    id: String,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let mut stats = client.stats(&id, Some(StatsOptions { stream: true }));
+    let mut failed = false;
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            sample = stats.next() => match sample {
+                Some(Ok(sample)) => {
+                    let mut app = app.lock().await;
+                    let stats = app.stats.entry(id.clone()).or_insert_with(|| ContainerStats::new(&sample.name));
+                    stats.push(&sample);
+                    // the cpu usage changes between refreshes, the order follows it
+                    let cpu = stats.latest.cpu_percent;
+                    app.container_list.get_mut().set_cpu(&id, Some(cpu));
+                }
+                Some(Err(err)) => {
+                    log::error!("Failed to read the stats of {}, {}", id, err);
+                    failed = true;
+                    break;
+                }
+                None => break,
+            }
+        }
+    }
+    {
+        let mut app = app.lock().await;
+        app.stats.remove(&id);
+        app.container_list.get_mut().set_cpu(&id, None);
+    }
+    // the refresh syncs the streams again, which restarts this one if the container still runs
+    if failed {
+        tokio::time::delay_for(STATS_RETRY).await;
+        if let Err(err) = io_tx.send(IOEvent::RefreshContainers) {
+            log::error!("Failed to send the message to refresh containers, {}", err)
+        }
+    }
+}
+
+/// Keeps exactly one stats stream per running container
+fn sync_stats_streams(
+    app: &Arc<Mutex<MainApp>>,
+    client: &Docker,
+    io_tx: &UnboundedSender<IOEvent>,
+    streams: &mut HashMap<String, oneshot::Sender<()>>,
+    containers: &[ContainerSummaryInner],
+) {
+    // the streams that ended dropped their end of the channel
+    streams.retain(|_, stop| !stop.is_closed());
+
+    let running: Vec<&String> = containers
+        .iter()
+        .filter(|container| container.state.as_deref() == Some("running"))
+        .filter_map(|container| container.id.as_ref())
+        .collect();
+
+    let gone: Vec<String> = streams
+        .keys()
+        .filter(|id| !running.contains(id))
+        .cloned()
+        .collect();
+    for id in gone {
+        if let Some(stop) = streams.remove(&id) {
+            let _ = stop.send(());
+        }
+    }
+
+    for id in running {
+        if !streams.contains_key(id) {
+            let (stop_tx, stop_rx) = oneshot::channel();
+            tokio::spawn(stream_stats(Arc::clone(app), client.clone(), io_tx.clone(), id.clone(), stop_rx));
+            streams.insert(id.clone(), stop_tx);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -60,53 +1132,430 @@ pub enum IOEvent {
     RefreshContainers,
     RefreshImages,
     RefreshVolumes,
+    RefreshNetworks,
+    /// Forgets the cached volume usage so the next refresh of the volumes fetches it again
+    VolumeUsageChanged,
+    Container { id: String, name: String, action: ContainerAction },
+    /// Runs the actions concurrently and sums up how each went, e.g. on the marked
+    /// items or the containers of a compose project
+    Bulk { title: String, actions: Vec<BulkAction> },
+    Network(NetworkAction),
+    Image(ImageAction),
+    Volume(VolumeAction),
+    /// Show the layer history and config of an image
+    ImageDetails { id: String, name: String },
+    /// Ask to confirm a prune, listing the images it would remove
+    PreviewImagePrune { all: bool },
+    /// Ask to confirm removing the volumes, checking every container, stopped ones included, for those in use
+    PreviewVolumeRemove { names: Vec<String> },
+    /// Pull an image reference, e.g. `nginx:1.19`, showing the progress in the pull popup
+    Pull(String),
+    CancelPull,
+    /// Follow the logs of the containers interleaved, or stop following when there are none
+    FollowLogs { streams: Vec<LogStream>, tail: usize },
+    /// Stream the stats of every running container while the stats tab is shown
+    WatchStats(bool),
+    RefreshVersion,
+    Inspect(InspectTarget),
+    /// Attach the terminal to the container's main process when it has a tty to attach to
+    Attach { id: String, name: String },
 }
 
 // Receive a message and handle it
 #[tokio::main]
-pub async fn start_tokio(app: &Arc<Mutex<MainApp>>, io_rx: std::sync::mpsc::Receiver<IOEvent>) {
-    while let Ok(event) = io_rx.recv() {
+pub async fn start_tokio(app: &Arc<Mutex<MainApp>>, mut io_rx: UnboundedReceiver<IOEvent>, io_tx: UnboundedSender<IOEvent>, host: String) {
+    let mut connection = ConnectionState::Connected;
+    let mut client = match get_client(&host) {
+        Ok(client) => client,
+        Err(err) => {
+            log::error!("Failed to create the docker client, {}", err);
+            set_connection(app, &mut connection, ConnectionState::Down(err.to_string())).await;
+            return;
+        }
+    };
+
+    tokio::spawn(watch_events(Arc::clone(app), io_tx.clone(), host.clone()));
+    let mut log_streams: Vec<oneshot::Sender<()>> = vec![];
+    let mut pull_stream: Option<oneshot::Sender<()>> = None;
+    let mut watch_stats = false;
+    let mut stats_streams: HashMap<String, oneshot::Sender<()>> = HashMap::new();
+    let mut volume_usage: HashMap<String, VolumeUsageData> = HashMap::new();
+    // reset when an action or a daemon event changed the usage so the next refresh fetches it again
+    let mut volume_usage_fetched: Option<Instant> = None;
+
+    while let Some(event) = io_rx.recv().await {
         log::debug!("Received event in loop {:?}", event);
+        if !connection.is_connected() {
+            reconnect(app, &mut connection, &mut client, &host).await;
+            if !connection.is_connected() {
+                continue;
+            }
+        }
         match event {
             IOEvent::RefreshContainers => {
-                let containers = get_containers().await;
+                let containers = get_containers(&client).await;
                 match containers {
                     Ok(containers) => {
+                        if watch_stats {
+                            sync_stats_streams(app, &client, &io_tx, &mut stats_streams, &containers);
+                        }
                         let mut app = app.lock().await;
                         log::debug!("Containers: {:?}", containers);
-                        app.containers = containers;
+                        app.set_containers(containers);
                     }
                     Err(err) => {
                         log::error!("There was an error retrieving containers, {}", err);
+                        track_error(app, &mut connection, &err).await;
                     }
                 }
             }
             IOEvent::RefreshImages => {
-                let images = get_images().await;
+                let images = get_images(&client).await;
                 match images {
                     Ok(images) => {
                         let mut app = app.lock().await;
                         log::debug!("Images: {:?}", images);
-                        app.images = images;
+                        app.set_images(images);
                     }
                     Err(err) => {
                         log::error!("There was an error retrieving images, {:?}", err);
+                        track_error(app, &mut connection, &err).await;
                     }
                 }
             }
             IOEvent::RefreshVolumes => {
-                let volumes = get_volumes().await;
+                if volume_usage_fetched.map_or(true, |fetched| fetched.elapsed() >= VOLUME_USAGE_TTL) {
+                    volume_usage = match get_volume_usage(&client).await {
+                        Ok(usage) => usage,
+                        Err(err) => {
+                            // the volumes are listed without usage until the next try
+                            log::warn!("Could not get volume usage, {:?}", err);
+                            HashMap::new()
+                        }
+                    };
+                    volume_usage_fetched = Some(Instant::now());
+                }
+                let volumes = get_volumes(&client, &volume_usage).await;
                 match volumes {
                     Ok(volumes) => {
                         let mut app = app.lock().await;
                         log::debug!("Volumes: {:?}", volumes);
-                        app.volumes = volumes.volumes;
+                        app.set_volumes(volumes);
                     }
                     Err(err) => {
                         log::error!("There was an error retrieving volumes, {:?}", err);
+                        track_error(app, &mut connection, &err).await;
+                    }
+                }
+            }
+            IOEvent::VolumeUsageChanged => volume_usage_fetched = None,
+            IOEvent::Container { id, name, action } => {
+                if let ContainerAction::Remove { .. } = action {
+                    volume_usage_fetched = None;
+                }
+                let result = run_container_action(&client, &id, &action).await;
+                if let Err(err) = &result {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                match result {
+                    Ok(_) => app.notice = Some(format!("{} {}", action.done(), name)),
+                    Err(err) => {
+                        log::error!("Failed to {:?} container {}, {}", action, name, err);
+                        app.notice = None;
+                        app.popup = Some(Popup::Message(MessagePopup::error(
+                            &format!("{} {} failed", action.pending(), name),
+                            err.to_string(),
+                        )));
+                    }
+                }
+                if let Err(err) = io_tx.send(IOEvent::RefreshContainers) {
+                    log::error!("Failed to send the message to refresh containers, {}", err)
+                }
+            }
+            IOEvent::Bulk { title, actions } => {
+                tokio::spawn(run_bulk(Arc::clone(app), client.clone(), io_tx.clone(), title, actions));
+            }
+            IOEvent::RefreshNetworks => {
+                match get_networks(&client).await {
+                    Ok(networks) => app.lock().await.set_networks(networks),
+                    Err(err) => {
+                        log::error!("There was an error retrieving networks, {:?}", err);
+                        track_error(app, &mut connection, &err).await;
+                    }
+                }
+            }
+            IOEvent::Network(action) => {
+                let result = run_network_action(&client, &action).await;
+                if let Err(err) = &result {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                match result {
+                    Ok(done) => app.notice = Some(done),
+                    Err(err) => {
+                        log::error!("Failed to {:?}, {}", action, err);
+                        app.notice = None;
+                        app.popup = Some(Popup::Message(MessagePopup::error(
+                            &format!("{} failed", action.pending()),
+                            err.to_string(),
+                        )));
+                    }
+                }
+                for event in [IOEvent::RefreshNetworks, IOEvent::RefreshContainers] {
+                    if let Err(err) = io_tx.send(event) {
+                        log::error!("Failed to send the message to refresh networks, {}", err)
+                    }
+                }
+            }
+            IOEvent::Image(action) => {
+                let result = run_image_action(&client, &action).await;
+                if let Err(err) = &result {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                match result {
+                    Ok(done) => app.notice = Some(done),
+                    Err(err) => {
+                        log::error!("Failed to {:?}, {}", action, err);
+                        let message = match (&action, &err) {
+                            (ImageAction::Remove { id, name, .. }, Error::DockerResponseConflictError { message }) => {
+                                image_conflict(&app, id, name, message)
+                            }
+                            _ => err.to_string(),
+                        };
+                        app.notice = None;
+                        app.popup = Some(Popup::Message(MessagePopup::error(
+                            &format!("{} failed", action.pending()),
+                            message,
+                        )));
+                    }
+                }
+                if let Err(err) = io_tx.send(IOEvent::RefreshImages) {
+                    log::error!("Failed to send the message to refresh images, {}", err)
+                }
+            }
+            IOEvent::Volume(action) => {
+                volume_usage_fetched = None;
+                let result = run_volume_action(&client, &action).await;
+                if let Err(err) = &result {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                match result {
+                    Ok(done) => app.notice = Some(done),
+                    Err(err) => {
+                        log::error!("Failed to {:?}, {}", action, err);
+                        let message = match (&action, &err) {
+                            (VolumeAction::Remove { name, .. }, Error::DockerResponseConflictError { message }) => {
+                                volume_conflict(&app, name, message)
+                            }
+                            _ => err.to_string(),
+                        };
+                        app.notice = None;
+                        app.popup = Some(Popup::Message(MessagePopup::error(
+                            &format!("{} failed", action.pending()),
+                            message,
+                        )));
+                    }
+                }
+                if let Err(err) = io_tx.send(IOEvent::RefreshVolumes) {
+                    log::error!("Failed to send the message to refresh volumes, {}", err)
+                }
+            }
+            IOEvent::ImageDetails { id, name } => {
+                let details = match get_image_history(&client, &id).await {
+                    Ok(history) => get_image_config(&client, &id).await.map(|config| (history, config)),
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = &details {
+                    track_error(app, &mut connection, err).await;
+                }
+                app.lock().await.popup = Some(match details {
+                    Ok((history, config)) => Popup::ImageDetails(ImageDetailsPopup::new(&name, history, config)),
+                    Err(err) => Popup::Message(MessagePopup::error(&format!("Details of {}", name), err.to_string())),
+                });
+            }
+            IOEvent::PreviewImagePrune { all } => {
+                let candidates = prune_candidates(&client, all).await;
+                if let Err(err) = &candidates {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                match candidates {
+                    Ok(images) if images.is_empty() => app.notice = Some("There are no images to prune".to_string()),
+                    Ok(images) => {
+                        let size: i64 = images.iter().map(|image| image.size.max(0)).sum();
+                        let mut lines = vec![format!(
+                            "Remove {} image{}, up to {}?",
+                            images.len(),
+                            if images.len() == 1 { "" } else { "s" },
+                            format_bytes(size as u64)
+                        )];
+                        lines.extend(images.iter().map(|image| {
+                            let tags = image.repo_tags.join(", ");
+                            format!("  {} {} {}", short_id(&image.id), tags, format_bytes(image.size.max(0) as u64))
+                        }));
+                        let action = ImageAction::Prune { all };
+                        app.popup = Some(Popup::Confirm(ConfirmPopup::new(
+                            &action.pending(),
+                            lines,
+                            vec![],
+                            Box::new(move |_| IOEvent::Image(action.clone())),
+                        )));
+                    }
+                    Err(err) => {
+                        app.popup = Some(Popup::Message(MessagePopup::error("Prune failed", err.to_string())));
+                    }
+                }
+            }
+            IOEvent::PreviewVolumeRemove { names } => {
+                let users = volume_users(&client, &names).await;
+                if let Err(err) = &users {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                app.popup = Some(match users {
+                    Ok(users) => volume_remove_popup(users),
+                    Err(err) => Popup::Message(MessagePopup::error("Remove volume failed", err.to_string())),
+                });
+            }
+            IOEvent::Pull(reference) => {
+                if let Some(stop) = pull_stream.take() {
+                    let _ = stop.send(());
+                }
+                let (stop_tx, stop_rx) = oneshot::channel();
+                tokio::spawn(stream_pull(Arc::clone(app), client.clone(), io_tx.clone(), reference, stop_rx));
+                pull_stream = Some(stop_tx);
+            }
+            IOEvent::CancelPull => {
+                if let Some(stop) = pull_stream.take() {
+                    let _ = stop.send(());
+                }
+            }
+            IOEvent::FollowLogs { streams, tail } => {
+                for stop in log_streams.drain(..) {
+                    let _ = stop.send(());
+                }
+                for stream in streams {
+                    let (stop_tx, stop_rx) = oneshot::channel();
+                    tokio::spawn(stream_logs(Arc::clone(app), client.clone(), stream.id, tail, stop_rx));
+                    log_streams.push(stop_tx);
+                }
+            }
+            IOEvent::RefreshVersion => {
+                let version = client.version().await;
+                if let Err(err) = &version {
+                    track_error(app, &mut connection, err).await;
+                }
+                let info = get_info(&host).await;
+                let version = VersionInfo::new(
+                    &host,
+                    version.map_err(|err| err.to_string()),
+                    info.map_err(|err| err.to_string()),
+                );
+                app.lock().await.version = Some(version);
+            }
+            IOEvent::Inspect(target) => {
+                let result = inspect(&client, &target).await;
+                if let Err(err) = &result {
+                    track_error(app, &mut connection, err).await;
+                }
+                app.lock().await.popup = Some(match result {
+                    Ok(value) => Popup::Inspect(InspectPopup::new(target.name(), value)),
+                    Err(err) => Popup::Message(MessagePopup::error(
+                        &format!("Inspect {}", target.name()),
+                        err.to_string(),
+                    )),
+                });
+            }
+            IOEvent::Attach { id, name } => {
+                let result = client.inspect_container(&id, None).await;
+                if let Err(err) = &result {
+                    track_error(app, &mut connection, err).await;
+                }
+                let mut app = app.lock().await;
+                match result.map(|container| container.config.unwrap_or_default()) {
+                    Ok(config) => {
+                        let tty = config.tty == Some(true);
+                        let open_stdin = config.open_stdin == Some(true);
+                        let session = Session::attach(&host, &id, &name, &app.options.detach_keys, tty, open_stdin);
+                        app.session = Some(session);
+                    }
+                    Err(err) => {
+                        app.popup = Some(Popup::Message(MessagePopup::error(&format!("Attach to {}", name), err.to_string())));
+                    }
+                }
+            }
+            IOEvent::WatchStats(watch) => {
+                watch_stats = watch;
+                if watch {
+                    let containers = app.lock().await.containers.clone();
+                    sync_stats_streams(app, &client, &io_tx, &mut stats_streams, &containers);
+                } else {
+                    for (_, stop) in stats_streams.drain() {
+                        let _ = stop.send(());
                     }
                 }
             }
         }
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refreshes(typ: &str, action: &str) -> Vec<String> {
+        let event = SystemEventsResponse {
+            typ: Some(typ.to_string()),
+            action: Some(action.to_string()),
+            ..Default::default()
+        };
+        refreshes_for(&event).iter().map(|event| format!("{:?}", event)).collect()
+    }
+
+    #[test]
+    fn container_events_refresh_the_containers() {
+        assert_eq!(refreshes("container", "start"), vec!["RefreshContainers"]);
+        assert_eq!(refreshes("container", "die"), vec!["RefreshContainers"]);
+    }
+
+    #[test]
+    fn created_and_destroyed_containers_change_the_volume_usage() {
+        let expected = vec!["RefreshContainers", "VolumeUsageChanged", "RefreshVolumes"];
+        assert_eq!(refreshes("container", "create"), expected);
+        assert_eq!(refreshes("container", "destroy"), expected);
+    }
+
+    #[test]
+    fn exec_and_attach_events_are_ignored() {
+        assert!(refreshes("container", "exec_start: sh -c true").is_empty());
+        assert!(refreshes("container", "exec_die").is_empty());
+        assert!(refreshes("container", "attach").is_empty());
+        assert!(refreshes("container", "resize").is_empty());
+    }
+
+    #[test]
+    fn other_objects_refresh_their_list() {
+        assert_eq!(refreshes("image", "pull"), vec!["RefreshImages"]);
+        assert_eq!(refreshes("volume", "mount"), vec!["VolumeUsageChanged", "RefreshVolumes"]);
+        assert_eq!(refreshes("network", "create"), vec!["RefreshNetworks"]);
+        assert_eq!(refreshes("network", "connect"), vec!["RefreshContainers", "RefreshNetworks"]);
+        assert!(refreshes("daemon", "reload").is_empty());
+    }
+
+    #[test]
+    fn split_reference_defaults_to_latest() {
+        assert_eq!(split_reference("nginx"), ("nginx", "latest"));
+        assert_eq!(split_reference("nginx:1.19"), ("nginx", "1.19"));
+        assert_eq!(split_reference("ghcr.io/owner/image:v2"), ("ghcr.io/owner/image", "v2"));
+    }
+
+    #[test]
+    fn split_reference_keeps_registry_ports_and_digests() {
+        assert_eq!(split_reference("localhost:5000/app"), ("localhost:5000/app", "latest"));
+        assert_eq!(split_reference("localhost:5000/app:1"), ("localhost:5000/app", "1"));
+        assert_eq!(split_reference("nginx@sha256:abc"), ("nginx@sha256:abc", ""));
+    }
+}
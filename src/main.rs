@@ -19,15 +19,21 @@ use tui::layout::{Direction, Margin, Rect};
 use tui::widgets::canvas::{Canvas, Map, MapResolution, Rectangle};
 use tui::widgets::Tabs;
 
+use crate::args::process_cmdline;
 use crate::components::main_app::MainApp;
 use crate::components::util::Config;
 use crate::components::util::event::{Event, Events};
+use crate::options::Options;
 use crate::style::{SharedTheme, Theme};
 use tokio::sync::Mutex;
 
 pub mod docker;
+mod args;
+mod clipboard;
+mod options;
 mod style;
 mod components;
+mod terminal;
 
 fn panic_hook(info: &PanicInfo<'_>) {
     if cfg!(debug_assertions) {
@@ -65,13 +71,18 @@ async fn main() -> Result<(), Error> {
 
     pretty_env_logger::init();
 
-    let (tx, rx) = std::sync::mpsc::channel();
+    let cliargs = process_cmdline()?;
+    let options = Options::init();
+    let host = docker::resolve_host(cliargs.host, &options);
+    docker::check_connection(&host).await?;
 
-    let app = Arc::new(Mutex::new(MainApp::new(tx)));
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let app = Arc::new(Mutex::new(MainApp::new(tx.clone(), options, host.clone(), cliargs.all)));
 
     let cloned_app = Arc::clone(&app);
     std::thread::spawn(move || {
-        docker::start_tokio(&app, rx);
+        docker::start_tokio(&app, rx, tx, host);
     });
 
     start_ui(&cloned_app).await?;
@@ -91,22 +102,33 @@ async fn start_ui(app: &Arc<Mutex<MainApp>>) -> Result<(), Error> {
         tick_rate: Duration::from_millis(250),
         ..Default::default()
     };
-    let events = Events::with_config(config);
+    let mut events = Events::with_config(config);
+    // 'q' also closes popups and is typed into searches, the app decides when to quit
+    events.disable_exit_key();
     let mut terminal = Terminal::new(backend)?;
 
     terminal.hide_cursor()?;
     terminal.clear()?;
 
     loop {
-        let mut app = app.lock().await;
-        terminal.draw(|f| {
-            &app.draw(f);
-        })?;
-
-        let should_break = app.handle_event(events.next())?;
-        if should_break {
-            break;
+        let session = {
+            let mut app = app.lock().await;
+            terminal.draw(|f| {
+                &app.draw(f);
+            })?;
+
+            let should_break = app.handle_event(events.next())?;
+            if should_break {
+                break;
+            };
+            app.session.take()
         };
+
+        // the app isn't held while the session runs so the IO worker keeps updating it
+        if let Some(session) = session {
+            let result = terminal::run_session(&mut terminal, &events, &session).await;
+            app.lock().await.session_finished(&session, result);
+        }
     }
 
     Ok(())
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+};
+use crate::get_app_config_path;
+use crate::components::containers::{ColumnConfig, ContainerSort};
+use crate::components::images::ImageSort;
+use crate::components::util::SortOrder;
+use crate::components::volumes::VolumeSort;
+
+/// User settings persisted next to the theme in the dockerui config dir
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Options {
+    /// Docker endpoint used when neither `--host` nor `DOCKER_HOST` is given
+    pub docker_host: Option<String>,
+    /// Number of lines fetched from the end of the logs when following a container
+    pub logs_tail: usize,
+    /// Maximum number of log lines kept in memory for the logs pane
+    pub logs_capacity: usize,
+    /// Show the daemon's timestamp in front of every log line
+    pub logs_timestamps: bool,
+    /// List stopped containers as well as running ones
+    pub all_containers: bool,
+    /// Group containers of a compose project, and of each of its services, in a tree
+    pub group_projects: bool,
+    /// Command run by exec, e.g. `["bash", "-l"]`. Empty tries bash and falls back to sh
+    pub exec_command: Vec<String>,
+    /// Key sequence that detaches from an attached container, in docker's `--detach-keys` format
+    pub detach_keys: String,
+    /// Columns of the containers table with their width in percent, in the order they are drawn
+    pub container_columns: Vec<ColumnConfig>,
+    /// Order of the containers, within their project when grouped
+    pub container_sort: SortOrder<ContainerSort>,
+    pub image_sort: SortOrder<ImageSort>,
+    pub volume_sort: SortOrder<VolumeSort>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            docker_host: None,
+            logs_tail: 200,
+            logs_capacity: 2000,
+            logs_timestamps: false,
+            all_containers: false,
+            group_projects: true,
+            exec_command: vec![],
+            detach_keys: "ctrl-p,ctrl-q".to_string(),
+            container_columns: ColumnConfig::defaults(),
+            container_sort: SortOrder::new(ContainerSort::Created),
+            image_sort: SortOrder::new(ImageSort::Created),
+            volume_sort: SortOrder::new(VolumeSort::Name),
+        }
+    }
+}
+
+impl Options {
+    pub fn save(&self) -> Result<()> {
+        let options_file = Self::get_options_file()?;
+        let mut file = File::create(options_file)?;
+        let data = to_string_pretty(self, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_options_file() -> Result<PathBuf> {
+        let app_home = get_app_config_path()?;
+        Ok(app_home.join("dockerui.ron"))
+    }
+
+    fn read_file(options_file: PathBuf) -> Result<Self> {
+        let mut f = File::open(options_file)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn init_internal() -> Result<Self> {
+        let file = Self::get_options_file()?;
+        if file.exists() {
+            Ok(Self::read_file(file)?)
+        } else {
+            let def = Self::default();
+            if def.save().is_err() {
+                log::warn!("failed to store default options to disk.")
+            }
+            Ok(def)
+        }
+    }
+
+    pub fn init() -> Self {
+        Self::init_internal().unwrap_or_else(|err| {
+            log::warn!("failed to read options, falling back to defaults: {}", err);
+            Self::default()
+        })
+    }
+}
@@ -193,6 +193,34 @@ impl Theme {
     //     self.apply_select(style, selected)
     // }
 
+    /// Colour of a container by its state as reported by the api, e.g. `running`
+    pub fn container_state(&self, state: &str) -> Style {
+        match state {
+            "running" => Style::default().fg(self.diff_line_add),
+            "paused" => Style::default().fg(self.diff_file_modified),
+            "restarting" | "removing" => Style::default().fg(self.diff_file_moved),
+            "dead" => Style::default().fg(self.danger_fg),
+            // exited and created containers are dimmed
+            _ => Style::default().fg(self.disabled_fg),
+        }
+    }
+
+    /// Colour of a compose project or service by how many of its containers run
+    pub fn container_group(&self, running: usize, total: usize) -> Style {
+        if running == total {
+            self.container_state("running")
+        } else if running > 0 {
+            Style::default().fg(self.diff_file_modified)
+        } else {
+            self.container_state("exited")
+        }
+    }
+
+    /// Chars matched by the filter of a pane, on top of the style of their item
+    pub fn filter_match(&self) -> Style {
+        Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    }
+
     pub fn text_danger(&self) -> Style {
         Style::default().fg(self.danger_fg)
     }
@@ -0,0 +1,316 @@
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tui::backend::Backend;
+use tui::Terminal;
+
+use crate::components::util::event::{poll_stdin, Events, INPUT_POLL};
+use crate::docker;
+
+/// Tries bash first and falls back to sh for images without it
+const DEFAULT_SHELL: [&str; 3] = ["sh", "-c", "if command -v bash >/dev/null; then exec bash; else exec sh; fi"];
+/// How often the terminal size is checked to pass a resize on to the container's tty
+const RESIZE_POLL: Duration = Duration::from_millis(250);
+/// What termion's `MouseTerminal` writes, mouse reports would otherwise be typed into a session
+const DISABLE_MOUSE: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l";
+const ENABLE_MOUSE: &str = "\x1b[?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h";
+
+/// Keys that end a session that only shows output, `q` and ctrl-c
+const LEAVE_KEYS: [u8; 2] = [b'q', 3];
+
+/// A shell or the main process of a container that takes over the terminal,
+/// streamed straight from the daemon. The terminal stays in raw mode, the
+/// container's tty does the echoing and line editing.
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// What is shown above the session's output, e.g. `Shell in web`
+    pub description: String,
+    /// How to get back to the ui
+    hint: String,
+    host: String,
+    container: String,
+    kind: SessionKind,
+}
+
+#[derive(Debug, Clone)]
+enum SessionKind {
+    Exec(Vec<String>),
+    Attach {
+        detach_keys: String,
+        /// Whether what is typed goes to the process, otherwise only its output is shown
+        interactive: bool,
+        /// Output without a tty comes with a header per chunk saying which stream it is from
+        tty: bool,
+    },
+}
+
+impl Session {
+    /// Interactive `command` in a running container, or bash/sh when it's empty
+    pub fn exec(host: &str, id: &str, name: &str, command: &[String]) -> Session {
+        let command = if command.is_empty() {
+            DEFAULT_SHELL.iter().map(|arg| arg.to_string()).collect()
+        } else {
+            command.to_vec()
+        };
+        Session {
+            description: format!("Shell in {}", name),
+            hint: "exit the shell to return to dockerui".to_string(),
+            host: host.to_string(),
+            container: id.to_string(),
+            kind: SessionKind::Exec(command),
+        }
+    }
+
+    /// The container's main process, `detach_keys` hands the terminal back, e.g. `ctrl-p,ctrl-q`.
+    /// Without a tty and open stdin typing can't reach it, its output is shown read-only.
+    pub fn attach(host: &str, id: &str, name: &str, detach_keys: &str, tty: bool, open_stdin: bool) -> Session {
+        let interactive = tty && open_stdin;
+        let hint = if interactive {
+            format!("detach with {} to return to dockerui", detach_keys)
+        } else {
+            "read-only, it has no tty with open stdin, q returns to dockerui".to_string()
+        };
+        Session {
+            description: format!("Attach to {}", name),
+            hint,
+            host: host.to_string(),
+            container: id.to_string(),
+            kind: SessionKind::Attach {
+                detach_keys: detach_keys.to_string(),
+                interactive,
+                tty,
+            },
+        }
+    }
+
+    /// Runs the session until the process exits or is detached from and
+    /// returns the exit code of a shell
+    async fn run(&self) -> Result<Option<i64>, Error> {
+        match &self.kind {
+            SessionKind::Exec(command) => {
+                let exec = docker::create_exec(&self.host, &self.container, command).await?;
+                let stream = docker::start_exec(&self.host, &exec).await?;
+                let resize_path = format!("/exec/{}/resize", exec);
+                forward(&self.host, stream, Some(&resize_path), true, true).await?;
+                docker::exec_exit_code(&self.host, &exec).await
+            }
+            SessionKind::Attach { detach_keys, interactive, tty } => {
+                let stream = docker::attach_container(&self.host, &self.container, detach_keys, *interactive).await?;
+                // only a tty can be resized
+                let resize_path = format!("/containers/{}/resize", self.container);
+                let resize_path = if *tty { Some(resize_path.as_str()) } else { None };
+                forward(&self.host, stream, resize_path, *interactive, *tty).await?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Hands the terminal over to `session` and restores the ui once it ends. The
+/// input thread is paused meanwhile so it doesn't steal keys from the session.
+pub async fn run_session<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &Events,
+    session: &Session,
+) -> Result<Option<i64>, Error> {
+    events.pause();
+    terminal.show_cursor()?;
+    let mut stdout = io::stdout();
+    write!(
+        stdout,
+        "{}{}{}{} ({})\r\n\r\n",
+        DISABLE_MOUSE,
+        termion::clear::All,
+        termion::cursor::Goto(1, 1),
+        session.description,
+        session.hint
+    )?;
+    stdout.flush()?;
+
+    let result = session.run().await;
+
+    write!(stdout, "{}", ENABLE_MOUSE)?;
+    terminal.hide_cursor()?;
+    // forget what was drawn before so the next frame repaints everything
+    terminal.clear()?;
+    events.resume();
+    result
+}
+
+/// Copies the stream's output to the terminal until the daemon closes it, and
+/// what is typed to the stream when `interactive`, otherwise only watching for
+/// the keys that leave. A tty at `resize_path` is resized with the terminal.
+async fn forward(
+    host: &str,
+    stream: hyper::upgrade::Upgraded,
+    resize_path: Option<&str>,
+    interactive: bool,
+    tty: bool,
+) -> Result<(), Error> {
+    let (mut output, mut input) = tokio::io::split(stream);
+    let (tx, mut typed) = mpsc::unbounded_channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader = read_stdin(tx, stop.clone());
+
+    let mut stdout = io::stdout();
+    let mut buffer = [0; 4096];
+    let mut frames = Frames::default();
+    let mut size = None;
+    let mut resize = tokio::time::interval(RESIZE_POLL);
+    let result = loop {
+        tokio::select! {
+            read = output.read(&mut buffer) => match read {
+                Ok(0) => break Ok(()),
+                Ok(read) => {
+                    let written = if tty {
+                        stdout.write_all(&buffer[..read])
+                    } else {
+                        stdout.write_all(&raw_newlines(&frames.push(&buffer[..read])))
+                    };
+                    if let Err(err) = written.and_then(|_| stdout.flush()) {
+                        break Err(err.into());
+                    }
+                }
+                Err(err) => break Err(err.into()),
+            },
+            Some(keys) = typed.recv() => {
+                if !interactive {
+                    if keys.iter().any(|key| LEAVE_KEYS.contains(key)) {
+                        break Ok(());
+                    }
+                } else if let Err(err) = input.write_all(&keys).await {
+                    break Err(err.into());
+                }
+            }
+            _ = resize.tick() => {
+                let current = termion::terminal_size().ok().filter(|current| size != Some(*current));
+                if let (Some(path), Some(current)) = (resize_path, current) {
+                    size = Some(current);
+                    // the session still works at the old size
+                    if let Err(err) = docker::resize_tty(host, path, current).await {
+                        log::warn!("failed to resize the tty: {}", err);
+                    }
+                }
+            }
+        }
+    };
+
+    // the reader has to be done with stdin before the input thread reads it again
+    stop.store(true, Ordering::SeqCst);
+    let _ = reader.join();
+    result
+}
+
+/// Sends what is typed until `stop` is set, polling so it notices without another key
+fn read_stdin(tx: mpsc::UnboundedSender<Vec<u8>>, stop: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let poll = match poll_stdin() {
+            Ok(poll) => poll,
+            Err(err) => {
+                log::warn!("failed to poll stdin, the session gets no input: {}", err);
+                return;
+            }
+        };
+        let mut events = mio::Events::with_capacity(1);
+        let mut buffer = [0; 1024];
+        while !stop.load(Ordering::SeqCst) {
+            if let Err(err) = poll.poll(&mut events, Some(INPUT_POLL)) {
+                log::error!("failed to poll stdin: {}", err);
+                return;
+            }
+            if events.is_empty() {
+                continue;
+            }
+            let read = match io::stdin().read(&mut buffer) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Ok(0) | Err(_) => return,
+                Ok(read) => read,
+            };
+            if tx.send(buffer[..read].to_vec()).is_err() {
+                return;
+            }
+        }
+    })
+}
+
+/// Without a tty nothing turns a bare newline into a new line in raw mode
+fn raw_newlines(output: &[u8]) -> Vec<u8> {
+    let mut lines = Vec::with_capacity(output.len());
+    for &byte in output {
+        if byte == b'\n' {
+            lines.push(b'\r');
+        }
+        lines.push(byte);
+    }
+    lines
+}
+
+/// Splits the output of a process without a tty, which comes in frames of an
+/// 8 byte header holding the stream and the payload's length, then the payload
+#[derive(Default)]
+struct Frames {
+    /// What was read of a frame that isn't complete yet
+    pending: Vec<u8>,
+}
+
+impl Frames {
+    /// The payloads of the frames `bytes` completes
+    fn push(&mut self, bytes: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(bytes);
+        let mut output = Vec::new();
+        let mut start = 0;
+        while self.pending.len() - start >= 8 {
+            let header = &self.pending[start..start + 8];
+            let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+            if self.pending.len() - start - 8 < length {
+                break;
+            }
+            output.extend_from_slice(&self.pending[start + 8..start + 8 + length]);
+            start += 8 + length;
+        }
+        self.pending.drain(..start);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(stream: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![stream, 0, 0, 0];
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[test]
+    fn frames_join_stdout_and_stderr() {
+        let mut frames = Frames::default();
+        let mut bytes = frame(1, b"out\n");
+        bytes.extend(frame(2, b"err\n"));
+        assert_eq!(frames.push(&bytes), b"out\nerr\n");
+        assert!(frames.pending.is_empty());
+    }
+
+    #[test]
+    fn frames_wait_for_the_rest_of_a_split_frame() {
+        let mut frames = Frames::default();
+        let bytes = frame(1, b"hello");
+        assert_eq!(frames.push(&bytes[..3]), b"");
+        assert_eq!(frames.push(&bytes[3..10]), b"");
+        assert_eq!(frames.push(&bytes[10..]), b"hello");
+    }
+
+    #[test]
+    fn raw_newlines_return_the_carriage() {
+        assert_eq!(raw_newlines(b"a\nb\n"), b"a\r\nb\r\n");
+        assert_eq!(raw_newlines(b"no newline"), b"no newline");
+    }
+}